@@ -1,9 +1,10 @@
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand};
+use clap::builder::PossibleValuesParser;
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
 
-use crate::tmux::Key;
+use crate::tmux::{Key, Socket};
 
 #[derive(Debug, Parser)]
 #[command(name = "muxi")]
@@ -11,6 +12,28 @@ use crate::tmux::Key;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Command,
+
+    /// Name of the tmux server socket (`tmux -L`)
+    #[arg(short = 'L', long, global = true, conflicts_with = "socket_path")]
+    pub socket_name: Option<String>,
+
+    /// Path of the tmux server socket (`tmux -S`)
+    #[arg(short = 'S', long, global = true)]
+    pub socket_path: Option<PathBuf>,
+
+    /// Print the tmux and shell commands instead of running them
+    #[arg(long, global = true, visible_alias = "print-commands")]
+    pub dry_run: bool,
+}
+
+impl Cli {
+    /// tmux server selected with `--socket-name` or `--socket-path`
+    pub fn tmux_socket(&self) -> Option<Socket> {
+        self.socket_path
+            .clone()
+            .map(Socket::Path)
+            .or_else(|| self.socket_name.clone().map(Socket::Name))
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -33,6 +56,12 @@ pub enum Command {
     #[clap(visible_alias = "p")]
     Plugins(Plugins),
 
+    /// Find projects under your configured roots and open them as sessions
+    Projects(Projects),
+
+    /// Generate files from your muxi config
+    Export(Export),
+
     /// Generate completions for your shell
     Completions { shell: Shell },
 
@@ -46,6 +75,16 @@ pub enum Command {
 
     #[clap(hide = true)]
     FzfKeybindings,
+
+    /// Run a Lua binding defined in your init.lua
+    #[clap(hide = true)]
+    RunBinding {
+        key: Key,
+
+        /// Key table the binding is in
+        #[arg(short, long, default_value = "muxi")]
+        table: String,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -88,8 +127,27 @@ pub enum SessionCommands {
         editor_args: Vec<String>,
     },
 
+    /// Switch to the previously used muxi session
+    Last,
+
     /// Print your current muxi sessions
-    List,
+    List {
+        /// Leave out the running tmux sessions that aren't bookmarked
+        #[arg(short, long)]
+        bookmarked: bool,
+    },
+
+    /// Switch to the next muxi session, in key order
+    Next,
+
+    /// Switch to the previous muxi session, in key order
+    Prev,
+
+    /// Preview a session: its active pane if running, what it would create otherwise
+    Preview {
+        /// Tmux key binding
+        key: Key,
+    },
 
     /// Set a binding for a new muxi session
     Set(SessionSetArgs),
@@ -126,6 +184,62 @@ pub struct SessionDeleteArgs {
     pub key: Key,
 }
 
+#[derive(Debug, Args)]
+pub struct Projects {
+    #[command(subcommand)]
+    pub command: Option<ProjectCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProjectCommands {
+    /// Print the projects found under `projects.roots`
+    List,
+
+    /// Go to the session of a project, creating it if needed
+    Switch(ProjectSwitchArgs),
+
+    /// Spawn a FZF popup to pick a project
+    Fzf {
+        /// Args forwarded to `fzf`
+        #[arg(last = true)]
+        fzf_args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectSwitchArgs {
+    /// Directory of the project
+    #[arg(required_unless_present_any = ["tmux_menu"])]
+    pub path: Option<PathBuf>,
+
+    /// Also bookmark the session to the first free key
+    #[arg(short, long)]
+    pub bookmark: bool,
+
+    /// Choose project from a native tmux menu (display-menu)
+    #[arg(short, long, conflicts_with = "path")]
+    pub tmux_menu: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct Export {
+    #[command(subcommand)]
+    pub command: ExportCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportCommands {
+    /// Render what `init` and `plugins init` do into a file for `tmux source-file`
+    TmuxConf(ExportTmuxConfArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExportTmuxConfArgs {
+    /// File to write (default: print to stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
 #[derive(Debug, Args)]
 pub struct Plugins {
     #[command(subcommand)]
@@ -135,17 +249,76 @@ pub struct Plugins {
 #[derive(Debug, Subcommand)]
 pub enum PluginCommands {
     /// Sources all plugins
-    Init,
+    Init(PluginNamesArgs),
 
     /// Print your current tmux plugins
     #[clap(visible_alias = "ls")]
-    List,
+    List(PluginNamesArgs),
 
     /// Install plugins
     #[clap(visible_alias = "i")]
-    Install,
+    Install(PluginNamesArgs),
 
     /// Update plugins
     #[clap(visible_alias = "up")]
-    Update,
+    Update(PluginNamesArgs),
+
+    /// Reset plugins to the revision they were at before their last update
+    Rollback(PluginRollbackArgs),
+
+    /// Import plugins and their options from a TPM setup
+    Import(PluginImportArgs),
+}
+
+#[derive(Debug, Args, Default)]
+pub struct PluginNamesArgs {
+    /// Names of the plugins (default: all plugins)
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct PluginRollbackArgs {
+    /// Name of the plugin (default: all plugins)
+    pub name: Option<String>,
+
+    /// List recorded revisions instead of rolling back
+    #[arg(short, long)]
+    pub list: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PluginImportArgs {
+    /// tmux config with your `@plugin` lines (default: ~/.tmux.conf or ~/.config/tmux/tmux.conf)
+    #[arg(long)]
+    pub tmux_conf: Option<PathBuf>,
+
+    /// Add the plugins to your init.lua instead of printing them
+    #[arg(short, long)]
+    pub write: bool,
+
+    /// Copy the plugins already cloned by TPM into muxi's plugins directory
+    #[arg(short, long, requires = "write")]
+    pub adopt: bool,
+
+    /// Where TPM cloned your plugins (default: ~/.tmux/plugins)
+    #[arg(long, requires = "adopt")]
+    pub tpm_dir: Option<PathBuf>,
+}
+
+/// Clap command offering `plugin_names` as completions for plugin arguments
+pub fn command_with_plugin_names(plugin_names: &[String]) -> clap::Command {
+    let names = PossibleValuesParser::new(plugin_names);
+
+    Cli::command().mut_subcommand("plugins", |plugins| {
+        ["init", "list", "install", "update"]
+            .into_iter()
+            .fold(plugins, |plugins, subcommand| {
+                plugins.mut_subcommand(subcommand, |command| {
+                    command.mut_arg("names", |arg| arg.value_parser(names.clone()))
+                })
+            })
+            .mut_subcommand("rollback", |command| {
+                command.mut_arg("name", |arg| arg.value_parser(names.clone()))
+            })
+    })
 }
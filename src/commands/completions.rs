@@ -0,0 +1,24 @@
+use clap_complete::Shell;
+use miette::Result;
+
+use crate::cli;
+use crate::muxi::Settings;
+
+pub fn completions(shell: Shell) -> Result<()> {
+    // A broken config shouldn't prevent generating completions
+    let plugin_names = Settings::from_lua()
+        .map(|settings| settings.plugins)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|plugin| plugin.name)
+        .collect::<Vec<_>>();
+
+    clap_complete::generate(
+        shell,
+        &mut cli::command_with_plugin_names(&plugin_names),
+        "muxi",
+        &mut std::io::stdout().lock(),
+    );
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ return {
   -- tmux_prefix = true,
   -- uppercase_overrides = true,
   -- use_current_pane_path = false,
+  -- session_sort = "key",
 
   plugins = {
     -- "tmux-plugins/tmux-resurrect",
@@ -15,6 +16,12 @@ return {
     -- args = { "--color=input-border:black" },
   },
 
+  options = {
+    -- global = { mouse = true, ["status-position"] = "top" },
+    -- server = { ["escape-time"] = 0 },
+    -- window = { ["mode-keys"] = "vi" },
+  },
+
   bindings = {
     -- e = {
     --   command = "muxi sessions edit",
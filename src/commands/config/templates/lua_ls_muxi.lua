@@ -5,10 +5,21 @@
 ---@field tmux_prefix? boolean
 ---@field uppercase_overrides? boolean
 ---@field use_current_pane_path? boolean
+---@field session_sort? "key"|"mru" Order of sessions in lists and menus (default: "key")
 ---@field editor? muxi.EditorSettings
 ---@field fzf? muxi.FzfSettings
 ---@field plugins? muxi.Plugin[]
----@field bindings? table<string, muxi.Binding>
+---@field git? muxi.GitSettings
+---@field bindings? table<string, muxi.Binding|muxi.Binding[]> A list binds the key in several tables
+---@field options? muxi.OptionsSettings
+---@field projects? muxi.ProjectsSettings
+
+---@class (exact) muxi.GitSettings
+---@field jobs? integer
+---@field timeout? integer
+---@field retries? integer
+---@field depth? integer
+---@field filter? string
 
 ---@class (exact) muxi.EditorSettings
 ---@field command? string
@@ -19,21 +30,61 @@
 ---@field bind_sessions? boolean
 ---@field args? string[]
 
+---@class (exact) muxi.OptionsSettings
+---@field global? table<string, muxi.OptionValue> set -g
+---@field server? table<string, muxi.OptionValue> set -s
+---@field window? table<string, muxi.OptionValue> set -gw
+---@field session? table<string, muxi.OptionValue> set (current session)
+
+---@class (exact) muxi.ProjectsSettings
+---@field roots? string[] Directories searched by `muxi projects`
+---@field max_depth? integer Levels below each root to search (default: 2)
+---@field markers? string[] Files or directories that make a directory a project (default: { ".git" })
+---@field ignore? string[] Globs of directories to skip (`*` and `?`), matched against their path relative to the root if they contain a `/`
+
+---@alias muxi.BindingKind "tmux"|"shell"|"popup"|"lua"
+
 ---@class (exact) muxi.Binding
----@field command string
+---@field command string|fun() A function for "lua" bindings
+---@field kind? muxi.BindingKind Default: "popup" if `popup` is set, "shell" otherwise
 ---@field popup? muxi.Popup
+---@field table? string Key table to bind into (default: "muxi")
+---@field repeatable? boolean `bind -r`
+---@field note? string `bind -N`
 
 ---@class (exact) muxi.Popup
 ---@field title? string
 ---@field width? string
 ---@field height? string
+---@field x? string Horizontal position (`-x`)
+---@field y? string Vertical position (`-y`)
+---@field border? "single"|"rounded"|"double"|"heavy"|"simple"|"padded"|"none" Default: "rounded"
+---@field style? string Popup style (`-s`)
+---@field border_style? string Border style (`-S`)
+---@field dir? string Working directory (default: session path, or pane path with `use_current_pane_path`)
+---@field env? table<string, string> Environment variables (`-e`)
+---@field close_on_exit? "always"|"success"|"never" Default: "always"
 
 ---@alias muxi.Plugin string|muxi.PluginSpec
 
 ---@class (exact) muxi.PluginSpec
 ---@field url? string
 ---@field path? string
----@field opts? table<string, string>
+---@field opts? table<string, muxi.OptionValue|muxi.TmuxOption>
+---@field depth? integer
+---@field filter? string
+
+---@alias muxi.OptionValue string|integer|boolean|(string|integer|boolean)[]
+
+---@alias muxi.OptionScope
+---| "global" # set -g (default)
+---| "server" # set -s
+---| "window" # set -gw
+---| "session" # set (current session)
+
+---@class (exact) muxi.TmuxOption
+---@field value muxi.OptionValue
+---@field scope? muxi.OptionScope
 
 ---@class (exact) muxi.Api
 ---@field config muxi.Config
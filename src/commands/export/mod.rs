@@ -0,0 +1,3 @@
+mod tmux_conf;
+
+pub use tmux_conf::*;
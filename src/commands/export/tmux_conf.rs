@@ -0,0 +1,48 @@
+use miette::{IntoDiagnostic, Result};
+use owo_colors::OwoColorize;
+
+use crate::cli::ExportTmuxConfArgs;
+use crate::muxi::{Muxi, Settings};
+use crate::tmux::{self, TmuxCommand, TmuxConf};
+
+pub fn tmux_conf(ExportTmuxConfArgs { output }: ExportTmuxConfArgs) -> Result<()> {
+    let settings = Settings::from_lua()?;
+    let sessions = Muxi::new()?.sessions;
+
+    let mut conf = TmuxConf::new();
+
+    conf.comment("Generated by `muxi export tmux-conf`, regenerate it instead of editing it")
+        .command(&tmux::clear_muxi_table_command())
+        .command(&tmux::init_command(&settings, &sessions));
+
+    for plugin in &settings.plugins {
+        conf.blank_line().comment(&plugin.name);
+
+        for (key, option) in &plugin.options {
+            conf.command(TmuxCommand::new().args(option.set_args(&format!("@{key}"))));
+        }
+
+        // On a fresh machine the conf is exported before the plugins are installed
+        if !plugin.is_installed() {
+            conf.comment("Not installed, run `muxi plugins install` and export again");
+            eprintln!(
+                "{} {} isn't installed, its scripts were left out",
+                "Warning:".yellow(),
+                plugin.name
+            );
+            continue;
+        }
+
+        for script in plugin.scripts()? {
+            conf.run_shell(&script);
+        }
+    }
+
+    match output {
+        Some(path) => std::fs::write(path, conf.to_string()).into_diagnostic(),
+        None => {
+            print!("{conf}");
+            Ok(())
+        }
+    }
+}
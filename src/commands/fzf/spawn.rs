@@ -1,10 +1,8 @@
-use std::process::{Command, Stdio};
-
 use miette::{IntoDiagnostic, Result};
 use owo_colors::OwoColorize;
 
 use crate::muxi::{Muxi, Settings};
-use crate::tmux::Key;
+use crate::tmux::{Key, SystemClient, TmuxClient, TmuxCommand};
 
 pub fn spawn(fzf_args: &[String]) -> Result<()> {
     let sessions = Muxi::new()?.sessions;
@@ -15,7 +13,7 @@ pub fn spawn(fzf_args: &[String]) -> Result<()> {
         return Ok(());
     }
 
-    let mut fzf_command = Command::new("tmux");
+    let mut fzf_command = TmuxCommand::new();
     fzf_command
         .arg("popup")
         .arg("-w")
@@ -51,21 +49,21 @@ pub fn spawn(fzf_args: &[String]) -> Result<()> {
         .arg("--preview-window")
         .arg("right,60%,<60(down,60%)")
         .arg("--preview")
-        .arg("tmux capture-pane -ep -t '{2}:'")
+        .arg("muxi sessions preview {1}")
         .arg("--bind")
-        .arg("start:reload:muxi sessions list")
+        .arg("start:reload:muxi sessions list --bookmarked")
         .arg("--bind")
         .arg("change:first")
         .arg("--bind")
         .arg("enter:execute(muxi sessions switch {1})+abort")
         .arg("--bind")
-        .arg("ctrl-x:execute-silent(muxi sessions delete {1})+reload(muxi sessions list)")
+        .arg("ctrl-x:execute-silent(muxi sessions delete {1})+reload(muxi sessions list --bookmarked)")
         .arg("--bind")
-        .arg("ctrl-r:execute(muxi sessions edit)+reload(muxi sessions list)")
+        .arg("ctrl-r:execute(muxi sessions edit)+reload(muxi sessions list --bookmarked)")
         .arg("--bind")
-        .arg("ctrl-g:execute(muxi config edit)+reload(muxi sessions list)")
+        .arg("ctrl-g:execute(muxi config edit)+reload(muxi sessions list --bookmarked)")
         .arg("--bind")
-        .arg("focus:change-preview(tmux capture-pane -ep -t '{2}:')+transform-preview-label(echo ' {2} ')")
+        .arg("focus:change-preview(muxi sessions preview {1})+transform-preview-label(echo ' {2} ')")
         .arg("--bind")
         .arg("?:change-preview(muxi fzf-keybindings)+change-preview-label( keybindings )+show-preview")
         .arg("--bind")
@@ -96,27 +94,23 @@ pub fn spawn(fzf_args: &[String]) -> Result<()> {
     fzf_command.args(settings.fzf.args).args(fzf_args);
 
     // Execute
-    fzf_command
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .into_diagnostic()?;
+    SystemClient.spawn(&fzf_command).into_diagnostic()?;
 
     Ok(())
 }
 
-fn bind_vim_keys(fzf_command: &mut Command) {
+fn bind_vim_keys(fzf_command: &mut TmuxCommand) {
     fzf_command
         .arg("--bind")
         .arg("j:down,k:up,q:abort")
         .arg("--bind")
         .arg("space:execute(muxi sessions switch {1})+abort")
         .arg("--bind")
-        .arg("d,x:execute-silent(muxi sessions delete {1})+reload(muxi sessions list)")
+        .arg("d,x:execute-silent(muxi sessions delete {1})+reload(muxi sessions list --bookmarked)")
         .arg("--bind")
-        .arg("e:execute(muxi sessions edit)+reload(muxi sessions list)")
+        .arg("e:execute(muxi sessions edit)+reload(muxi sessions list --bookmarked)")
         .arg("--bind")
-        .arg("c:execute(muxi config edit)+reload(muxi sessions list)")
+        .arg("c:execute(muxi config edit)+reload(muxi sessions list --bookmarked)")
         .arg("--bind")
         .arg("p:toggle-preview")
         .arg("--bind")
@@ -125,7 +119,7 @@ fn bind_vim_keys(fzf_command: &mut Command) {
         .arg("i,/:show-input+unbind(j,k,q,space,d,x,e,c,p,r,i,/)");
 }
 
-fn bind_alt_session_keys(fzf_command: &mut Command, muxi_session_keys: &[String]) {
+fn bind_alt_session_keys(fzf_command: &mut TmuxCommand, muxi_session_keys: &[String]) {
     for key in muxi_session_keys {
         fzf_command.arg("--bind").arg(format!(
             "alt-{key}:execute(muxi sessions switch {key})+abort"
@@ -133,7 +127,7 @@ fn bind_alt_session_keys(fzf_command: &mut Command, muxi_session_keys: &[String]
     }
 }
 
-fn bind_raw_session_keys(fzf_command: &mut Command, muxi_session_keys: &[String]) {
+fn bind_raw_session_keys(fzf_command: &mut TmuxCommand, muxi_session_keys: &[String]) {
     fzf_command.arg("--bind").arg(format!(
         "i,/:show-input+unbind(j,k,q,d,x,e,c,p,r,i,/,{})",
         muxi_session_keys.join(",")
@@ -146,10 +140,10 @@ fn bind_raw_session_keys(fzf_command: &mut Command, muxi_session_keys: &[String]
     }
 }
 
-fn bind_session_overrides(fzf_command: &mut Command) {
+fn bind_session_overrides(fzf_command: &mut TmuxCommand) {
     for key in 'A'..='Z' {
         fzf_command.arg("--bind").arg(format!(
-            "alt-{key}:execute-silent(muxi sessions set {})+reload(muxi sessions list)",
+            "alt-{key}:execute-silent(muxi sessions set {})+reload(muxi sessions list --bookmarked)",
             key.to_lowercase()
         ));
     }
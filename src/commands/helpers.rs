@@ -1,5 +1,6 @@
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
 
 use miette::bail;
 use miette::{IntoDiagnostic, Result};
@@ -40,3 +41,12 @@ pub fn open_editor_for(path: &Path, editor_args: &[String]) -> Result<()> {
         bail!("Edit failed")
     }
 }
+
+pub fn format_relative_time(time: SystemTime) -> String {
+    let now = SystemTime::now();
+    let duration = now
+        .duration_since(time)
+        .unwrap_or_else(|_| Duration::from_secs(0));
+
+    timeago::Formatter::new().convert(duration)
+}
@@ -7,7 +7,7 @@ pub fn init() -> Result<()> {
     let muxi = Muxi::new()?;
     let settings = Settings::from_lua()?;
 
-    tmux::init(&settings, &muxi.sessions)?;
+    tmux::init(&tmux::SystemClient, &settings, &muxi.sessions)?;
 
     Ok(())
 }
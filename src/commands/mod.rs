@@ -1,9 +1,18 @@
 pub mod config;
+pub mod export;
 pub mod fzf;
 pub mod plugins;
+pub mod projects;
 pub mod sessions;
 
+mod completions;
 mod helpers;
 mod init;
+mod run_binding;
 
+pub use completions::*;
 pub use init::*;
+pub use run_binding::*;
+
+pub use crate::tmux::set_dry_run;
+pub use crate::tmux::set_socket as set_tmux_socket;
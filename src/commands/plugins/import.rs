@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use miette::{IntoDiagnostic, Result, WrapErr, miette};
+use owo_colors::OwoColorize;
+
+use crate::cli::PluginImportArgs;
+use crate::muxi::{Settings, TpmImport, insert_into_plugins_table, path};
+
+pub fn import(
+    PluginImportArgs {
+        tmux_conf,
+        write,
+        adopt,
+        tpm_dir,
+    }: PluginImportArgs,
+) -> Result<()> {
+    let tmux_conf = tmux_conf.map_or_else(path::tmux_conf_file, path::expand_tilde);
+    let contents = std::fs::read_to_string(&tmux_conf)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to read {}", tmux_conf.display()))?;
+
+    let import = TpmImport::parse(&contents).without(&Settings::from_lua()?.plugins);
+
+    if import.is_empty() {
+        println!("{}", "Nothing to import!".red());
+        return Ok(());
+    }
+
+    if write {
+        if adopt {
+            let tpm_dir = tpm_dir.map_or_else(path::tpm_plugins_dir, path::expand_tilde);
+            adopt_plugins(&import, &tpm_dir)?;
+        }
+
+        write_init_lua(&import)
+    } else {
+        println!(
+            "{}",
+            "-- Add to the `plugins` table of your init.lua".dimmed()
+        );
+        print!("{}", import.to_lua(""));
+        Ok(())
+    }
+}
+
+fn adopt_plugins(import: &TpmImport, tpm_dir: &Path) -> Result<()> {
+    for imported in &import.plugins {
+        let name = &imported.plugin.name;
+
+        if imported.plugin.adopt(tpm_dir)? {
+            println!("{} {name}", "adopted".green().bold());
+        } else {
+            println!("{} {name}", "skipped".yellow().bold());
+        }
+    }
+
+    Ok(())
+}
+
+fn write_init_lua(import: &TpmImport) -> Result<()> {
+    let init_lua = path::settings_file();
+
+    if !init_lua.exists() {
+        return Err(miette!(
+            help = "Run `muxi config init` to create one",
+            "{} does not exist",
+            init_lua.display()
+        ));
+    }
+
+    let contents = std::fs::read_to_string(&init_lua).into_diagnostic()?;
+
+    let updated = insert_into_plugins_table(&contents, &import.to_lua("")).ok_or_else(|| {
+        miette!(
+            help = "Run `muxi plugins import` without `--write` and paste the plugins yourself",
+            "Couldn't find a `plugins = {{` table in {}",
+            init_lua.display()
+        )
+    })?;
+
+    std::fs::write(&init_lua, updated).into_diagnostic()?;
+
+    println!(
+        "{} {}",
+        "updated".cyan().bold(),
+        init_lua.display().to_string().dimmed()
+    );
+    println!("Run `muxi plugins install` to install the new plugins");
+
+    Ok(())
+}
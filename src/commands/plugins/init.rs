@@ -1,12 +1,25 @@
 use std::sync::Mutex;
 use std::thread;
 
+use super::select::select_plugins;
 use super::ui;
-use crate::muxi::Settings;
+use crate::cli::PluginNamesArgs;
+use crate::muxi::{AppliedOptions, Settings, unset_plugin_options};
+use crate::tmux::{SystemClient, dry_run};
 use miette::Result;
 
-pub fn init() -> Result<()> {
-    let plugins = Settings::from_lua()?.plugins;
+pub fn init(PluginNamesArgs { names }: PluginNamesArgs) -> Result<()> {
+    let plugins = select_plugins(Settings::from_lua()?.plugins, &names)?;
+    let client = SystemClient;
+
+    // Unset the options removed from the config since the last init
+    let mut applied_options = AppliedOptions::load()?;
+    let stale_options = applied_options.track(&plugins, names.is_empty());
+    unset_plugin_options(&client, &stale_options)?;
+
+    if !dry_run() {
+        applied_options.save()?;
+    }
 
     if plugins.is_empty() {
         return Ok(());
@@ -17,7 +30,7 @@ pub fn init() -> Result<()> {
     thread::scope(|s| {
         for plugin in plugins {
             s.spawn(|| {
-                if let Err(error) = plugin.source() {
+                if let Err(error) = plugin.source(&client) {
                     errors.lock().unwrap().push((plugin, error));
                 }
             });
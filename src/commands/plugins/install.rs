@@ -1,38 +1,42 @@
 use std::sync::Mutex;
-use std::thread;
 
 use indicatif::MultiProgress;
 use miette::Result;
 use owo_colors::OwoColorize;
 
+use super::pool::for_each_plugin;
+use super::select::select_plugins;
 use super::ui::{self, PluginSpinner};
+use crate::cli::PluginNamesArgs;
 use crate::muxi::Settings;
 
-pub fn install() -> Result<()> {
-    let plugins = Settings::from_lua()?.plugins;
+pub fn install(PluginNamesArgs { names }: PluginNamesArgs) -> Result<()> {
+    let Settings {
+        plugins,
+        git: git_settings,
+        ..
+    } = Settings::from_lua()?;
 
     if plugins.is_empty() {
         println!("{}", "No plugins defined!".red());
         return Ok(());
     }
 
+    let plugins = select_plugins(plugins, &names)?;
+
     let multi = MultiProgress::new();
     let errors = Mutex::new(Vec::new());
 
-    thread::scope(|s| {
-        for plugin in plugins {
-            s.spawn(|| {
-                let spinner = PluginSpinner::new(&multi, &plugin.name);
-
-                match plugin.install() {
-                    Ok(true) => spinner.finish_success(None),
-                    Ok(false) => spinner.finish_already_installed(),
-                    Err(error) => {
-                        spinner.finish_error();
-                        errors.lock().unwrap().push((plugin, error));
-                    }
-                }
-            });
+    for_each_plugin(plugins, git_settings.jobs, |_, plugin| {
+        let spinner = PluginSpinner::new(&multi, &plugin.name);
+
+        match plugin.install(&git_settings) {
+            Ok(true) => spinner.finish_success(None),
+            Ok(false) => spinner.finish_already_installed(),
+            Err(error) => {
+                spinner.finish_error(&error);
+                errors.lock().unwrap().push((plugin, error));
+            }
         }
     });
 
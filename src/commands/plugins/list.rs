@@ -1,9 +1,11 @@
 use miette::Result;
 use owo_colors::OwoColorize;
 
+use super::select::select_plugins;
+use crate::cli::PluginNamesArgs;
 use crate::muxi::{PluginStatus, Settings};
 
-pub fn list() -> Result<()> {
+pub fn list(PluginNamesArgs { names }: PluginNamesArgs) -> Result<()> {
     let plugins = Settings::from_lua()?.plugins;
 
     if plugins.is_empty() {
@@ -11,6 +13,8 @@ pub fn list() -> Result<()> {
         return Ok(());
     }
 
+    let plugins = select_plugins(plugins, &names)?;
+
     for plugin in plugins {
         print_plugin_status(&plugin)?;
 
@@ -1,10 +1,16 @@
+mod import;
 mod init;
 mod install;
 mod list;
+mod pool;
+mod rollback;
+mod select;
 mod ui;
 mod update;
 
+pub use import::*;
 pub use init::*;
 pub use install::*;
 pub use list::*;
+pub use rollback::*;
 pub use update::*;
@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+use std::thread;
+
+use crate::muxi::Plugin;
+
+/// Calls `f` with every plugin (and its position), using at most `jobs` threads
+pub fn for_each_plugin<F>(plugins: Vec<Plugin>, jobs: usize, f: F)
+where
+    F: Fn(usize, Plugin) + Sync,
+{
+    let workers = jobs.clamp(1, plugins.len().max(1));
+    let queue = Mutex::new(plugins.into_iter().enumerate());
+
+    thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| {
+                loop {
+                    let next = queue.lock().unwrap().next();
+                    let Some((index, plugin)) = next else {
+                        break;
+                    };
+
+                    f(index, plugin);
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    fn plugins(count: usize) -> Vec<Plugin> {
+        (0..count)
+            .map(|index| Plugin::parse(&format!("owner/plugin-{index}")).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn for_each_plugin_visits_every_plugin() {
+        let visited = Mutex::new(Vec::new());
+
+        for_each_plugin(plugins(5), 2, |index, plugin| {
+            visited.lock().unwrap().push((index, plugin.name));
+        });
+
+        let mut visited = visited.into_inner().unwrap();
+        visited.sort();
+
+        assert_eq!(
+            visited,
+            (0..5)
+                .map(|index| (index, format!("plugin-{index}")))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn for_each_plugin_respects_jobs() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        for_each_plugin(plugins(6), 2, |_, _| {
+            let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(current, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+    }
+}
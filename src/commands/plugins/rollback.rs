@@ -0,0 +1,92 @@
+use indicatif::MultiProgress;
+use miette::Result;
+use owo_colors::OwoColorize;
+
+use super::select::select_plugins;
+use super::ui::{self, PluginSpinner};
+use crate::cli::PluginRollbackArgs;
+use crate::commands::helpers::format_relative_time;
+use crate::muxi::{Plugin, PluginRevision, PluginRollbackStatus, Settings};
+
+pub fn rollback(PluginRollbackArgs { name, list }: PluginRollbackArgs) -> Result<()> {
+    let plugins = Settings::from_lua()?.plugins;
+
+    if plugins.is_empty() {
+        println!("{}", "No plugins defined!".red());
+        return Ok(());
+    }
+
+    let plugins = select_plugins(plugins, name.as_slice())?;
+
+    if list {
+        return list_revisions(&plugins);
+    }
+
+    let multi = MultiProgress::new();
+    let mut errors = Vec::new();
+
+    for plugin in plugins {
+        let spinner = PluginSpinner::new(&multi, &plugin.name);
+
+        match plugin.rollback() {
+            Ok(PluginRollbackStatus::RolledBack { from, to }) => {
+                spinner.finish_success(Some(&format!("{from}..{to}")));
+            }
+            Ok(PluginRollbackStatus::NoHistory) => {
+                spinner.finish_up_to_date(Some("no recorded revisions"));
+            }
+            Ok(PluginRollbackStatus::Local { path }) => {
+                spinner.finish_up_to_date(Some(&path));
+            }
+            Err(error) => {
+                spinner.finish_error(&error);
+                errors.push((plugin, error));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ui::format_plugin_errors(&errors, "roll back"))
+    }
+}
+
+fn list_revisions(plugins: &[Plugin]) -> Result<()> {
+    let mut logs = Vec::new();
+
+    for plugin in plugins {
+        let revisions = plugin.history()?;
+
+        if !revisions.is_empty() {
+            logs.push(format_plugin_revisions(&plugin.name, &revisions));
+        }
+    }
+
+    if logs.is_empty() {
+        println!("{}", "No recorded revisions!".red());
+    } else {
+        println!("{}", logs.join("\n\n"));
+    }
+
+    Ok(())
+}
+
+fn format_plugin_revisions(plugin_name: &str, revisions: &[PluginRevision]) -> String {
+    let header = plugin_name.bold().to_string();
+
+    let body = revisions
+        .iter()
+        .map(|revision| {
+            format!(
+                "  {} {} {}",
+                revision.id.green().bold(),
+                revision.summary.trim(),
+                format!("(replaced {})", format_relative_time(revision.time)).dimmed()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("{header}\n{body}")
+}
@@ -0,0 +1,58 @@
+use miette::{Result, miette};
+
+use crate::muxi::Plugin;
+
+/// Keeps the plugins named in `names`, or all of them if `names` is empty
+pub fn select_plugins(plugins: Vec<Plugin>, names: &[String]) -> Result<Vec<Plugin>> {
+    if names.is_empty() {
+        return Ok(plugins);
+    }
+
+    let missing = names
+        .iter()
+        .filter(|name| !plugins.iter().any(|plugin| &plugin.name == *name))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        return Err(miette!("Plugin not found: {}", missing.join(", ")));
+    }
+
+    Ok(plugins
+        .into_iter()
+        .filter(|plugin| names.contains(&plugin.name))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugins() -> Vec<Plugin> {
+        vec![
+            Plugin::parse("tmux-plugins/tmux-continuum").unwrap(),
+            Plugin::parse("tmux-plugins/tmux-resurrect").unwrap(),
+        ]
+    }
+
+    #[test]
+    fn select_plugins_without_names_keeps_all() {
+        assert_eq!(select_plugins(plugins(), &[]).unwrap(), plugins());
+    }
+
+    #[test]
+    fn select_plugins_by_name() {
+        let selected = select_plugins(plugins(), &["tmux-resurrect".into()]).unwrap();
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "tmux-resurrect");
+    }
+
+    #[test]
+    fn select_plugins_reports_unknown_names() {
+        let error = select_plugins(plugins(), &["tmux-yank".into(), "tmux-continuum".into()])
+            .expect_err("expected unknown plugin to fail");
+
+        assert!(format!("{error}").contains("tmux-yank"));
+    }
+}
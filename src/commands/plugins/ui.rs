@@ -1,7 +1,7 @@
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
 
-use crate::muxi::Plugin;
+use crate::muxi::{GitTimeout, Plugin};
 
 pub fn format_plugin_errors(
     errors: &[(Plugin, miette::Report)],
@@ -29,6 +29,7 @@ enum PluginSpinnerResult {
     AlreadyInstalled,
     Error,
     Success,
+    TimedOut,
     UpToDate,
 }
 
@@ -64,8 +65,13 @@ impl PluginSpinner {
         self.finish_with(PluginSpinnerResult::Success, detail);
     }
 
-    pub fn finish_error(&self) {
-        self.finish_with(PluginSpinnerResult::Error, None);
+    pub fn finish_error(&self, error: &miette::Report) {
+        if let Some(timeout) = error.downcast_ref::<GitTimeout>() {
+            let detail = format!("timed out after {}s", timeout.timeout.as_secs());
+            self.finish_with(PluginSpinnerResult::TimedOut, Some(&detail));
+        } else {
+            self.finish_with(PluginSpinnerResult::Error, None);
+        }
     }
 
     pub fn finish_up_to_date(&self, detail: Option<&str>) {
@@ -86,6 +92,7 @@ impl PluginSpinner {
             PluginSpinnerResult::AlreadyInstalled => "⊙".blue().bold().to_string(),
             PluginSpinnerResult::Error => "✗".red().bold().to_string(),
             PluginSpinnerResult::Success => "✔".green().bold().to_string(),
+            PluginSpinnerResult::TimedOut => "⧗".yellow().bold().to_string(),
             PluginSpinnerResult::UpToDate => "≡".blue().bold().to_string(),
         };
 
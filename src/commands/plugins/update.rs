@@ -1,71 +1,70 @@
 use std::sync::Mutex;
-use std::thread;
-use std::time::{Duration, SystemTime};
 
 use indicatif::MultiProgress;
 use miette::Result;
 use owo_colors::OwoColorize;
 
+use super::pool::for_each_plugin;
+use super::select::select_plugins;
 use super::ui::{self, PluginSpinner};
+use crate::cli::PluginNamesArgs;
+use crate::commands::helpers::format_relative_time;
 use crate::muxi::{PluginChange, PluginUpdateStatus, Settings};
 
-pub fn update() -> Result<()> {
-    let plugins = Settings::from_lua()?.plugins;
+pub fn update(PluginNamesArgs { names }: PluginNamesArgs) -> Result<()> {
+    let Settings {
+        plugins,
+        git: git_settings,
+        ..
+    } = Settings::from_lua()?;
 
     if plugins.is_empty() {
         println!("{}", "No plugins defined!".red());
         return Ok(());
     }
 
+    let plugins = select_plugins(plugins, &names)?;
+
     let multi = MultiProgress::new();
     let errors = Mutex::new(Vec::new());
     let changelogs = Mutex::new(Vec::new());
 
-    thread::scope(|s| {
-        for (index, plugin) in plugins.into_iter().enumerate() {
-            let progress = &multi;
-            let errors_ref = &errors;
-            let changelogs = &changelogs;
-
-            s.spawn(move || {
-                let spinner = PluginSpinner::new(progress, &plugin.name);
-                let plugin_name = plugin.name.clone();
-
-                match plugin.update() {
-                    Ok(PluginUpdateStatus::Updated {
-                        from,
-                        to,
-                        changes,
-                        range_url,
-                    }) => {
-                        let display = match from {
-                            Some(from) => format!("{from}..{to}"),
-                            None => to,
-                        };
-                        let detail = if let Some(url) = range_url.as_ref() {
-                            ui::hyperlink(&display, url)
-                        } else {
-                            display
-                        };
-                        spinner.finish_success(Some(&detail));
-
-                        if !changes.is_empty() {
-                            let log = format_plugin_changes(&plugin_name, &changes);
-                            changelogs.lock().unwrap().push((index, log));
-                        }
-                    }
-                    Ok(PluginUpdateStatus::UpToDate { commit }) => {
-                        spinner.finish_up_to_date(Some(&commit));
-                    }
-                    Ok(PluginUpdateStatus::Local { path }) => {
-                        spinner.finish_up_to_date(Some(&path));
-                    }
-                    Err(error) => {
-                        spinner.finish_error();
-                        errors_ref.lock().unwrap().push((plugin.clone(), error));
-                    }
+    for_each_plugin(plugins, git_settings.jobs, |index, plugin| {
+        let spinner = PluginSpinner::new(&multi, &plugin.name);
+
+        match plugin.update(&git_settings) {
+            Ok(PluginUpdateStatus::Updated {
+                from,
+                to,
+                changes,
+                range_url,
+            }) => {
+                let display = match from {
+                    Some(from) => format!("{from}..{to}"),
+                    None => to,
+                };
+                let detail = if let Some(url) = range_url.as_ref() {
+                    ui::hyperlink(&display, url)
+                } else {
+                    display
+                };
+                spinner.finish_success(Some(&detail));
+
+                if !changes.is_empty() {
+                    let log = format_plugin_changes(&plugin.name, &changes);
+                    changelogs.lock().unwrap().push((index, log));
                 }
-            });
+            }
+            Ok(PluginUpdateStatus::UpToDate { commit }) => {
+                spinner.finish_up_to_date(Some(&commit));
+            }
+            Ok(PluginUpdateStatus::Local { path }) => {
+                spinner.finish_up_to_date(Some(&path));
+            }
+            Err(error) => {
+                spinner.finish_error(&error);
+                errors.lock().unwrap().push((plugin, error));
+            }
         }
     });
 
@@ -114,12 +113,3 @@ fn format_plugin_changes(plugin_name: &str, changes: &[PluginChange]) -> String
 
     format!("{header}\n{body}")
 }
-
-fn format_relative_time(time: SystemTime) -> String {
-    let now = SystemTime::now();
-    let duration = now
-        .duration_since(time)
-        .unwrap_or_else(|_| Duration::from_secs(0));
-
-    timeago::Formatter::new().convert(duration)
-}
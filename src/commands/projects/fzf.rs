@@ -0,0 +1,54 @@
+use miette::{IntoDiagnostic, Result};
+
+use crate::muxi::Settings;
+use crate::tmux::{SystemClient, TmuxClient, TmuxCommand};
+
+pub fn fzf(fzf_args: &[String]) -> Result<()> {
+    let settings = Settings::from_lua()?;
+
+    let mut fzf_command = TmuxCommand::new();
+    fzf_command
+        .arg("popup")
+        .arg("-w")
+        .arg("80%")
+        .arg("-h")
+        .arg("80%")
+        .arg("-b")
+        .arg("none")
+        .arg("-E")
+        .arg("fzf")
+        .arg("--reverse")
+        .arg("--info")
+        .arg("inline-right")
+        .arg("--highlight-line")
+        .arg("--list-border")
+        .arg("--list-label")
+        .arg(" muxi projects ")
+        .arg("--input-border")
+        .arg("--color")
+        .arg("list-label:green")
+        .arg("--color")
+        .arg("list-border:black")
+        .arg("--color")
+        .arg("input-border:black")
+        .arg("--prompt")
+        .arg("❯ ")
+        .arg("--ghost")
+        .arg("muxi projects")
+        .arg("--bind")
+        .arg("start:reload:muxi projects list")
+        .arg("--bind")
+        .arg("change:first")
+        .arg("--bind")
+        .arg("enter:execute(muxi projects switch {})+abort")
+        .arg("--bind")
+        .arg("ctrl-b:execute(muxi projects switch --bookmark {})+abort");
+
+    // Append user provided args
+    fzf_command.args(settings.fzf.args).args(fzf_args);
+
+    // Execute
+    SystemClient.spawn(&fzf_command).into_diagnostic()?;
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use miette::Result;
+use owo_colors::OwoColorize;
+
+use crate::muxi::Settings;
+
+pub fn list() -> Result<()> {
+    let settings = Settings::from_lua()?;
+
+    if settings.projects.roots.is_empty() {
+        println!("{}", "No project roots defined!".red());
+        return Ok(());
+    }
+
+    for project in settings.projects.discover() {
+        println!("{}", project.display());
+    }
+
+    Ok(())
+}
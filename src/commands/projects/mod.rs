@@ -0,0 +1,7 @@
+mod fzf;
+mod list;
+mod switch;
+
+pub use fzf::*;
+pub use list::*;
+pub use switch::*;
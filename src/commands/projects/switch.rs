@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use miette::{IntoDiagnostic, Result, miette};
+use owo_colors::OwoColorize;
+
+use crate::cli::ProjectSwitchArgs;
+use crate::commands::{self, sessions};
+use crate::muxi::{Muxi, Sessions, Settings, disambiguate_session, path, project_session};
+use crate::tmux;
+
+pub fn switch(
+    ProjectSwitchArgs {
+        path,
+        bookmark,
+        tmux_menu,
+    }: ProjectSwitchArgs,
+) -> Result<()> {
+    if tmux_menu {
+        return self::tmux_menu();
+    }
+
+    // Clap only requires the path without `--tmux-menu`
+    let Some(path) = path else {
+        return Err(miette!("A project path is required"));
+    };
+
+    let path = std::path::absolute(path::expand_tilde(path)).into_diagnostic()?;
+
+    if !path.is_dir() {
+        println!("{}", "Project not found!".red());
+        return Ok(());
+    }
+
+    let mut sessions = Muxi::new()?.sessions;
+
+    // Already bookmarked
+    if let Some(key) = sessions
+        .0
+        .iter()
+        .find_map(|(key, session)| path::same_path(&session.path, &path).then(|| key.clone()))
+    {
+        return sessions::switch(&key);
+    }
+
+    let client = tmux::SystemClient;
+    let session = disambiguate_session(project_session(&path), &tmux::list_sessions(&client)?);
+
+    if bookmark {
+        let settings = Settings::from_lua()?;
+        let key = sessions
+            .free_key(|key| {
+                settings
+                    .bindings
+                    .get(key)
+                    .is_some_and(|bindings| bindings.iter().any(|binding| binding.table == "muxi"))
+            })
+            .ok_or_else(|| miette!("No free key to bookmark {}", session.name))?;
+
+        // A dry run doesn't touch sessions.toml, so it opens the session as is
+        if tmux::dry_run() {
+            let bookmark = Sessions(BTreeMap::from([(key.clone(), session.clone())]));
+
+            println!(
+                "# Bookmark {} to {key} in {}",
+                session.name,
+                path::sessions_file().display()
+            );
+
+            for line in bookmark.to_toml()?.lines() {
+                println!("# {line}");
+            }
+        } else {
+            sessions.0.insert(key.clone(), session);
+            sessions.save()?;
+
+            // Outside of tmux the bindings are created by the next `muxi init`
+            if tmux::within_tmux().is_ok() {
+                commands::init()?;
+            }
+
+            return sessions::switch(&key);
+        }
+    }
+
+    if !tmux::has_session(&client, &session) {
+        tmux::create_session(&client, &session)?;
+    }
+
+    if tmux::within_tmux().is_ok() {
+        tmux::switch_to(&client, &session)?;
+    } else {
+        tmux::attach_to(&client, &session)?;
+    }
+
+    Ok(())
+}
+
+pub fn tmux_menu() -> Result<()> {
+    let client = tmux::SystemClient;
+    let settings = Settings::from_lua()?;
+    let projects = settings.projects.discover();
+
+    if projects.is_empty() {
+        println!("{}", "No projects found!".red());
+        return Ok(());
+    }
+
+    tmux::projects_menu(&client, &projects)?;
+
+    Ok(())
+}
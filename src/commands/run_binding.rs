@@ -0,0 +1,10 @@
+use miette::Result;
+
+use crate::muxi::{Settings, lua, path};
+use crate::tmux::Key;
+
+pub fn run_binding(key: &Key, table: &str) -> Result<()> {
+    lua::run_binding(&path::muxi_dir(), &Settings::default(), key, table)?;
+
+    Ok(())
+}
@@ -1,17 +1,212 @@
 use miette::Result;
 use owo_colors::OwoColorize;
 
-use crate::muxi::Muxi;
+use crate::commands::helpers::format_relative_time;
+use crate::muxi::{Muxi, Session, Settings};
+use crate::tmux::{self, Key, TmuxSession};
 
-pub fn list() -> Result<()> {
+/// Shown in place of the key for tmux sessions without a bookmark
+const UNBOUND_KEY: &str = "-";
+
+/// With `bookmarked`, only the bookmarked sessions are listed, e.g. for FZF
+/// whose actions target the first field as a key
+pub fn list(bookmarked: bool) -> Result<()> {
+    let client = tmux::SystemClient;
     let sessions = Muxi::new()?.sessions;
+    // A broken config shouldn't prevent listing the sessions
+    let session_sort = Settings::from_lua()
+        .map(|settings| settings.session_sort)
+        .unwrap_or_default();
+    let tmux_sessions = tmux::list_sessions(&client)?;
 
-    if sessions.is_empty() {
+    if sessions.is_empty() && (bookmarked || tmux_sessions.is_empty()) {
         println!("{}", "No sessions defined!".red());
         return Ok(());
     }
 
-    println!("{sessions}");
+    println!(
+        "{}",
+        format_sessions(&sessions.sorted(session_sort)?, &tmux_sessions, !bookmarked)
+    );
 
     Ok(())
 }
+
+/// Bookmarked sessions annotated with their tmux state,
+/// followed by the running sessions that aren't bookmarked if `with_unbookmarked`
+fn format_sessions(
+    sessions: &[(&Key, &Session)],
+    tmux_sessions: &[TmuxSession],
+    with_unbookmarked: bool,
+) -> String {
+    let bookmarked = sessions.iter().map(|(key, session)| {
+        let tmux_session = tmux_sessions
+            .iter()
+            .find(|tmux_session| tmux_session.name == session.name);
+
+        (
+            key.to_string(),
+            session.name.clone(),
+            session.display_path(),
+            tmux_session,
+        )
+    });
+
+    let unbookmarked = tmux_sessions
+        .iter()
+        .filter(|tmux_session| {
+            with_unbookmarked
+                && !sessions
+                    .iter()
+                    .any(|(_, session)| session.name == tmux_session.name)
+        })
+        .map(|tmux_session| {
+            (
+                UNBOUND_KEY.to_string(),
+                tmux_session.name.clone(),
+                display_path(tmux_session),
+                Some(tmux_session),
+            )
+        });
+
+    let rows = bookmarked.chain(unbookmarked).collect::<Vec<_>>();
+
+    let max_width_key = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let max_width_name = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+    let max_width_path = rows.iter().map(|row| row.2.len()).max().unwrap_or(0);
+
+    rows.iter()
+        .map(|(key, name, path, tmux_session)| {
+            let key = if key == UNBOUND_KEY {
+                format!("{key:<max_width_key$}").dimmed().to_string()
+            } else {
+                format!("{key:<max_width_key$}").green().to_string()
+            };
+
+            format!(
+                "{key}  {:<max_width_name$}  {:<max_width_path$}  {}",
+                name.blue(),
+                path.dimmed(),
+                format_state(*tmux_session)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_state(tmux_session: Option<&TmuxSession>) -> String {
+    let Some(tmux_session) = tmux_session else {
+        return format!("{} {}", "○".dimmed(), "stopped".dimmed());
+    };
+
+    let windows = if tmux_session.windows == 1 {
+        "1 window".to_string()
+    } else {
+        format!("{} windows", tmux_session.windows)
+    };
+
+    let attached = if tmux_session.is_attached() {
+        "attached".yellow().to_string()
+    } else {
+        "detached".dimmed().to_string()
+    };
+
+    format!(
+        "{} {} {} {} {} {}",
+        "●".green(),
+        windows,
+        "·".dimmed(),
+        attached,
+        "·".dimmed(),
+        format_relative_time(tmux_session.last_activity).dimmed()
+    )
+}
+
+fn display_path(tmux_session: &TmuxSession) -> String {
+    if let Some(home_dir) = dirs::home_dir()
+        && let Ok(stripped) = tmux_session.path.strip_prefix(&home_dir)
+    {
+        return format!("~/{}", stripped.display());
+    }
+
+    tmux_session.path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::SystemTime;
+
+    use crate::muxi::Sessions;
+
+    use super::*;
+
+    fn tmux_session(name: &str, attached: usize) -> TmuxSession {
+        TmuxSession {
+            name: name.into(),
+            path: format!("/code/{name}").into(),
+            attached,
+            windows: 2,
+            last_activity: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_format_sessions_annotates_state() {
+        let sessions = Sessions(BTreeMap::from([
+            (
+                "d".into(),
+                Session {
+                    name: "dotfiles".into(),
+                    path: "/code/dotfiles".into(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "m".into(),
+                Session {
+                    name: "muxi".into(),
+                    path: "/code/muxi".into(),
+                    ..Default::default()
+                },
+            ),
+        ]));
+        let tmux_sessions = [tmux_session("muxi", 1), tmux_session("scratch", 0)];
+
+        let sessions = sessions.0.iter().collect::<Vec<_>>();
+
+        let lines = format_sessions(&sessions, &tmux_sessions, true)
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with(&"d".green().to_string()));
+        assert!(lines[0].ends_with(&format_state(None)));
+        assert!(lines[1].starts_with(&"m".green().to_string()));
+        assert!(lines[1].contains(&"attached".yellow().to_string()));
+        assert!(lines[2].starts_with(&UNBOUND_KEY.dimmed().to_string()));
+        assert!(lines[2].contains("scratch"));
+        assert!(lines[2].contains(&"detached".dimmed().to_string()));
+    }
+
+    #[test]
+    fn test_format_sessions_bookmarked_only() {
+        let sessions = Sessions(BTreeMap::from([(
+            "m".into(),
+            Session {
+                name: "muxi".into(),
+                path: "/code/muxi".into(),
+                ..Default::default()
+            },
+        )]));
+        let tmux_sessions = [tmux_session("muxi", 1), tmux_session("scratch", 0)];
+
+        let sessions = sessions.0.iter().collect::<Vec<_>>();
+
+        let formatted = format_sessions(&sessions, &tmux_sessions, false);
+
+        assert_eq!(formatted.lines().count(), 1);
+        assert!(!formatted.contains("scratch"));
+    }
+}
@@ -1,11 +1,13 @@
 mod delete;
 mod edit;
 mod list;
+mod preview;
 mod set;
 mod switch;
 
 pub use delete::*;
 pub use edit::*;
 pub use list::*;
+pub use preview::*;
 pub use set::*;
 pub use switch::*;
@@ -0,0 +1,314 @@
+use std::path::Path;
+use std::process::Command;
+
+use miette::Result;
+use owo_colors::OwoColorize;
+
+use crate::muxi::{Muxi, OnCreateAction, Session};
+use crate::tmux;
+
+/// Max number of directory entries shown for a session that isn't running
+const MAX_ENTRIES: usize = 20;
+
+/// Max number of `git status` lines shown for a session that isn't running
+const MAX_GIT_STATUS_LINES: usize = 10;
+
+pub fn preview(key: &tmux::Key) -> Result<()> {
+    let client = tmux::SystemClient;
+    let sessions = Muxi::new()?.sessions;
+
+    let Some(session) = sessions.0.get(key) else {
+        println!("{}", "Session not found!".red());
+        return Ok(());
+    };
+
+    if tmux::has_session(&client, session) {
+        print!("{}", tmux::capture_pane(&client, session)?);
+    } else {
+        println!("{}", format_stopped_session(session));
+    }
+
+    Ok(())
+}
+
+fn format_stopped_session(session: &Session) -> String {
+    let mut sections = vec![format!(
+        "{} {}\n{}",
+        session.name.blue().bold(),
+        "(not running)".dimmed(),
+        session.display_path().dimmed()
+    )];
+
+    if session.path.is_dir() {
+        sections.push(format_section("Files", &list_directory(&session.path)));
+
+        if let Some(status) = git_status(&session.path) {
+            sections.push(format_section("Git", &status));
+        }
+    } else {
+        sections.push("Path does not exist".red().to_string());
+    }
+
+    if !session.env.is_empty() {
+        let env = session
+            .env
+            .iter()
+            .map(|(name, value)| format!("{}={value}", name.green()))
+            .collect::<Vec<_>>();
+
+        sections.push(format_section("Environment", &env));
+    }
+
+    if session.window_name.is_some() || session.command.is_some() {
+        sections.push(format_section(
+            "First window",
+            &[format_window(
+                session.window_name.as_deref(),
+                &session.path,
+                session.command.as_deref(),
+            )],
+        ));
+    }
+
+    if !session.on_create.is_empty() {
+        let plan = session
+            .on_create
+            .iter()
+            .map(|action| format_on_create_action(session, action))
+            .collect::<Vec<_>>();
+
+        sections.push(format_section("On create", &plan));
+    }
+
+    if let Some(select) = &session.select {
+        sections.push(format!(
+            "{} {}",
+            "Selects".bold().underline(),
+            select.bold()
+        ));
+    }
+
+    sections.join("\n\n")
+}
+
+fn format_section(title: &str, lines: &[String]) -> String {
+    format!("{}\n{}", title.bold().underline(), lines.join("\n"))
+}
+
+/// Sorted entries of `path`, directories first with a trailing `/`
+fn list_directory(path: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return vec!["Can't read directory".red().to_string()];
+    };
+
+    let mut entries = entries
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            (!is_dir, entry.file_name().to_string_lossy().to_string())
+        })
+        .collect::<Vec<_>>();
+
+    entries.sort();
+
+    let total = entries.len();
+
+    let mut lines = entries
+        .into_iter()
+        .take(MAX_ENTRIES)
+        .map(|(is_file, name)| {
+            if is_file {
+                name
+            } else {
+                format!("{name}/").blue().to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if total > MAX_ENTRIES {
+        lines.push(
+            format!("… and {} more", total - MAX_ENTRIES)
+                .dimmed()
+                .to_string(),
+        );
+    }
+
+    lines
+}
+
+/// Branch and changes of the repository at `path`, if any
+/// Equivalent to: `git -C <path> status --short --branch`
+fn git_status(path: &Path) -> Option<Vec<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("status")
+        .arg("--short")
+        .arg("--branch")
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines().map(str::to_string).collect::<Vec<_>>();
+    let total = lines.len();
+
+    if total > MAX_GIT_STATUS_LINES {
+        lines.truncate(MAX_GIT_STATUS_LINES);
+        lines.push(
+            format!("… and {} more", total - MAX_GIT_STATUS_LINES)
+                .dimmed()
+                .to_string(),
+        );
+    }
+
+    Some(lines)
+}
+
+fn format_on_create_action(session: &Session, action: &OnCreateAction) -> String {
+    match action {
+        OnCreateAction::NewWindow(new_window) => format!(
+            "{} {}",
+            "new window".green(),
+            format_window(
+                new_window.name.as_deref(),
+                &session.on_create_path(new_window.path.as_deref()),
+                new_window.command.as_deref(),
+            )
+        ),
+        OnCreateAction::SendKeys(send_keys) => {
+            let mut line = "send keys".green().to_string();
+
+            if let Some(target) = &send_keys.target {
+                line.push_str(&format!(" {}", target.bold()));
+            }
+
+            line.push_str(&format!(" {} {}", "❯".dimmed(), send_keys.keys));
+            line
+        }
+        OnCreateAction::Run(run) => {
+            let mut line = format!("{} {} {}", "run".green(), "❯".dimmed(), run.command);
+
+            if run.background {
+                line.push_str(&format!(" {}", "(background)".dimmed()));
+            }
+
+            line
+        }
+    }
+}
+
+/// `[<name>] <path> [❯ <command>]`
+fn format_window(name: Option<&str>, path: &Path, command: Option<&str>) -> String {
+    let mut parts = Vec::new();
+
+    if let Some(name) = name {
+        parts.push(name.bold().to_string());
+    }
+
+    parts.push(path.display().dimmed().to_string());
+
+    if let Some(command) = command {
+        parts.push(format!("{} {command}", "❯".dimmed()));
+    }
+
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use crate::muxi::{NewWindow, SendKeys};
+
+    use super::*;
+
+    #[test]
+    fn test_list_directory_sorts_directories_first() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(temp.path().join("README.md"), "").unwrap();
+
+        let entries = list_directory(temp.path());
+
+        assert_eq!(
+            entries,
+            vec![
+                "src/".blue().to_string(),
+                "Cargo.toml".to_string(),
+                "README.md".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_directory_truncates() {
+        let temp = tempfile::tempdir().unwrap();
+
+        for index in 0..MAX_ENTRIES + 3 {
+            std::fs::write(temp.path().join(format!("file-{index:02}")), "").unwrap();
+        }
+
+        let entries = list_directory(temp.path());
+
+        assert_eq!(entries.len(), MAX_ENTRIES + 1);
+        assert_eq!(
+            entries.last().unwrap(),
+            &"… and 3 more".dimmed().to_string()
+        );
+    }
+
+    #[test]
+    fn test_format_send_keys_action() {
+        let session = Session {
+            name: "muxi".into(),
+            path: PathBuf::from("/code/muxi"),
+            ..Default::default()
+        };
+        let action = OnCreateAction::SendKeys(SendKeys {
+            keys: "cargo test".into(),
+            target: Some("editor.1".into()),
+        });
+
+        assert_eq!(
+            format_on_create_action(&session, &action),
+            format!(
+                "{} {} {} cargo test",
+                "send keys".green(),
+                "editor.1".bold(),
+                "❯".dimmed()
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_on_create_action() {
+        let session = Session {
+            name: "muxi".into(),
+            path: PathBuf::from("/code/muxi"),
+            ..Default::default()
+        };
+        let action = OnCreateAction::NewWindow(NewWindow {
+            path: Some("src".into()),
+            name: Some("editor".into()),
+            command: Some("nvim".into()),
+            env: BTreeMap::new(),
+        });
+
+        assert_eq!(
+            format_on_create_action(&session, &action),
+            format!(
+                "{} {} {} {} nvim",
+                "new window".green(),
+                "editor".bold(),
+                Path::new("/code/muxi/src").display().dimmed(),
+                "❯".dimmed()
+            )
+        );
+    }
+}
@@ -3,22 +3,27 @@ use miette::miette;
 
 use crate::cli::SessionSetArgs;
 use crate::commands;
-use crate::muxi::{Muxi, Session, Settings};
+use crate::muxi::{self, Muxi, Session, Settings};
 use crate::tmux;
 
 pub fn set(SessionSetArgs { key, name, path }: SessionSetArgs) -> Result<()> {
+    if muxi::is_reserved_key(&key) {
+        return Err(muxi::Error::ReservedKey(key).into());
+    }
+
+    let client = tmux::SystemClient;
     let settings = Settings::from_lua()?;
 
     let name = name
-        .or_else(tmux::current_session_name)
+        .or_else(|| tmux::current_session_name(&client))
         .ok_or_else(|| miette!("Couldn't find current session name"))?;
 
     let path = path
         .or_else(|| {
             if settings.use_current_pane_path {
-                tmux::current_pane_path()
+                tmux::current_pane_path(&client)
             } else {
-                tmux::current_session_path()
+                tmux::current_session_path(&client)
             }
         })
         .ok_or_else(|| miette!("Couldn't find current path"))?;
@@ -30,7 +35,7 @@ pub fn set(SessionSetArgs { key, name, path }: SessionSetArgs) -> Result<()> {
         Session {
             name,
             path,
-            on_create: Vec::new(),
+            ..Default::default()
         },
     );
     sessions.save()?;
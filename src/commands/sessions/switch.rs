@@ -1,10 +1,11 @@
 use miette::Result;
 use owo_colors::OwoColorize;
 
-use crate::muxi::Muxi;
+use crate::muxi::{Muxi, SessionHistory, Settings};
 use crate::tmux;
 
 pub fn switch(key: &tmux::Key) -> Result<()> {
+    let client = tmux::SystemClient;
     let sessions = Muxi::new()?.sessions;
 
     let Some(session) = sessions.0.get(key) else {
@@ -12,18 +13,83 @@ pub fn switch(key: &tmux::Key) -> Result<()> {
         return Ok(());
     };
 
-    if !tmux::has_session(session) {
-        tmux::create_session(session)?;
+    if !tmux::has_session(&client, session) {
+        tmux::create_session(&client, session)?;
     }
 
-    tmux::switch_to(session)?;
+    let mut history = SessionHistory::load()?;
+    history.record(key);
+
+    // A dry run doesn't touch the history
+    let save_history = || {
+        if tmux::dry_run() {
+            Ok(())
+        } else {
+            history.save()
+        }
+    };
+
+    if tmux::within_tmux().is_ok() {
+        tmux::switch_to(&client, session)?;
+        save_history()?;
+    } else {
+        // Attaching replaces this process, so save the history first
+        save_history()?;
+        tmux::attach_to(&client, session)?;
+    }
 
     Ok(())
 }
 
+/// Switch to the most recently used session other than the current one
+pub fn last() -> Result<()> {
+    let client = tmux::SystemClient;
+    let sessions = Muxi::new()?.sessions;
+    let history = SessionHistory::load()?;
+    let current_session = tmux::current_session_name(&client);
+
+    let Some((key, _)) = history.last(&sessions, current_session.as_deref()) else {
+        println!("{}", "No previous session!".red());
+        return Ok(());
+    };
+
+    switch(key)
+}
+
+/// Switch to the bookmark after the current session's, in key order
+pub fn next() -> Result<()> {
+    let client = tmux::SystemClient;
+    let sessions = Muxi::new()?.sessions;
+    let current_session = tmux::current_session_name(&client);
+
+    let Some(key) = sessions.next_key(current_session.as_deref()) else {
+        println!("{}", "No sessions defined!".red());
+        return Ok(());
+    };
+
+    switch(key)
+}
+
+/// Switch to the bookmark before the current session's, in key order
+pub fn prev() -> Result<()> {
+    let client = tmux::SystemClient;
+    let sessions = Muxi::new()?.sessions;
+    let current_session = tmux::current_session_name(&client);
+
+    let Some(key) = sessions.prev_key(current_session.as_deref()) else {
+        println!("{}", "No sessions defined!".red());
+        return Ok(());
+    };
+
+    switch(key)
+}
+
 pub fn tmux_menu() -> Result<()> {
+    let client = tmux::SystemClient;
     let sessions = Muxi::new()?.sessions;
-    tmux::sessions_menu(&sessions)?;
+    let settings = Settings::from_lua()?;
+
+    tmux::sessions_menu(&client, &sessions.sorted(settings.session_sort)?)?;
 
     Ok(())
 }
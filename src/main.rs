@@ -1,21 +1,38 @@
-use clap::{CommandFactory, Parser};
+use clap::Parser;
 use miette::Result;
-use muxi::cli::{Cli, Command, ConfigCommands, PluginCommands, SessionCommands};
-use muxi::commands::{self, config, fzf, plugins, sessions};
+use muxi::cli::{
+    Cli, Command, ConfigCommands, ExportCommands, PluginCommands, PluginNamesArgs, ProjectCommands,
+    SessionCommands,
+};
+use muxi::commands::{self, config, export, fzf, plugins, projects, sessions};
 
 fn main() -> Result<()> {
     let app = Cli::parse();
 
+    if let Some(socket) = app.tmux_socket() {
+        commands::set_tmux_socket(socket);
+    }
+
+    if app.dry_run {
+        commands::set_dry_run();
+    }
+
     match app.command {
         Command::Init => commands::init(),
-        Command::Ls => sessions::list(),
+        Command::Ls => sessions::list(false),
         Command::Sessions(sessions_command) => {
             // Default to `list` if no command given
-            let command = sessions_command.command.unwrap_or(SessionCommands::List);
+            let command = sessions_command
+                .command
+                .unwrap_or(SessionCommands::List { bookmarked: false });
 
             match command {
                 SessionCommands::Edit { editor_args } => sessions::edit(&editor_args),
-                SessionCommands::List => sessions::list(),
+                SessionCommands::Last => sessions::last(),
+                SessionCommands::List { bookmarked } => sessions::list(bookmarked),
+                SessionCommands::Next => sessions::next(),
+                SessionCommands::Prev => sessions::prev(),
+                SessionCommands::Preview { key } => sessions::preview(&key),
                 SessionCommands::Delete(options) => sessions::delete(options),
                 SessionCommands::Set(options) => sessions::set(options),
                 SessionCommands::Switch { key, tmux_menu } => {
@@ -30,13 +47,17 @@ fn main() -> Result<()> {
         }
         Command::Plugins(plugins_command) => {
             // Default to `list` if no command given
-            let command = plugins_command.command.unwrap_or(PluginCommands::List);
+            let command = plugins_command
+                .command
+                .unwrap_or_else(|| PluginCommands::List(PluginNamesArgs::default()));
 
             match command {
-                PluginCommands::Init => plugins::init(),
-                PluginCommands::List => plugins::list(),
-                PluginCommands::Install => plugins::install(),
-                PluginCommands::Update => plugins::update(),
+                PluginCommands::Init(options) => plugins::init(options),
+                PluginCommands::List(options) => plugins::list(options),
+                PluginCommands::Install(options) => plugins::install(options),
+                PluginCommands::Update(options) => plugins::update(options),
+                PluginCommands::Rollback(options) => plugins::rollback(options),
+                PluginCommands::Import(options) => plugins::import(options),
             }
         }
         Command::Config(config_command) => {
@@ -49,17 +70,22 @@ fn main() -> Result<()> {
                 ConfigCommands::Edit { editor_args } => config::edit(&editor_args),
             }
         }
-        Command::Completions { shell } => {
-            clap_complete::generate(
-                shell,
-                &mut Cli::command(),
-                "muxi",
-                &mut std::io::stdout().lock(),
-            );
+        Command::Projects(projects_command) => {
+            // Default to `list` if no command given
+            let command = projects_command.command.unwrap_or(ProjectCommands::List);
 
-            Ok(())
+            match command {
+                ProjectCommands::List => projects::list(),
+                ProjectCommands::Switch(options) => projects::switch(options),
+                ProjectCommands::Fzf { fzf_args } => projects::fzf(&fzf_args),
+            }
         }
+        Command::Export(export_command) => match export_command.command {
+            ExportCommands::TmuxConf(options) => export::tmux_conf(options),
+        },
+        Command::Completions { shell } => commands::completions(shell),
         Command::Fzf { fzf_args } => fzf::spawn(&fzf_args),
         Command::FzfKeybindings => fzf::keybindings::show(),
+        Command::RunBinding { key, table } => commands::run_binding(&key, &table),
     }
 }
@@ -0,0 +1,159 @@
+use mlua::Value as LuaValue;
+use mlua::prelude::LuaTable;
+use std::path::Path;
+
+use crate::muxi::Settings;
+use crate::tmux::Key;
+
+use super::error::Error;
+use super::parse::load_config;
+
+/// Calls the function set as `command` of the binding for `key` in `table` in `init.lua`
+pub fn run_binding(path: &Path, settings: &Settings, key: &Key, table: &str) -> Result<(), Error> {
+    let (_lua, muxi_config) = load_config(path, settings)?;
+
+    let Some(key_bindings) = muxi_config
+        .get::<Option<LuaTable>>("bindings")?
+        .map(|bindings| bindings.get::<Option<LuaTable>>(key.as_ref()))
+        .transpose()?
+        .flatten()
+    else {
+        return Err(Error::BindingNotFound(key.to_string()));
+    };
+
+    // A key holds either a single binding or a list of them
+    let bindings = if key_bindings.raw_len() > 0 {
+        key_bindings
+            .sequence_values::<LuaTable>()
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![key_bindings]
+    };
+
+    let mut function = None;
+
+    for binding in bindings {
+        let binding_table = binding
+            .get::<Option<String>>("table")?
+            .unwrap_or_else(|| "muxi".to_string());
+
+        if binding_table == table
+            && let LuaValue::Function(command) = binding.get::<LuaValue>("command")?
+        {
+            function = Some(command);
+            break;
+        }
+    }
+
+    let Some(function) = function else {
+        return Err(Error::BindingNotFound(key.to_string()));
+    };
+
+    function.call::<()>(()).map_err(|source| Error::Binding {
+        key: key.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_init_lua<F>(config: &str, test: F)
+    where
+        F: Fn(&Path),
+    {
+        let pwd = tempfile::tempdir().unwrap();
+        std::fs::write(pwd.path().join("init.lua"), config).unwrap();
+
+        test(pwd.path());
+    }
+
+    #[test]
+    fn test_run_binding_calls_function() {
+        let config = r#"
+            return {
+              bindings = {
+                x = {
+                  kind = "lua",
+                  command = function()
+                    local file = io.open(muxi_marker, "w")
+                    file:write("called")
+                    file:close()
+                  end,
+                },
+              },
+            }
+        "#;
+
+        with_init_lua(config, |pwd| {
+            let marker = pwd.join("marker");
+            let config = format!("muxi_marker = {:?}\n{config}", marker.display().to_string());
+            std::fs::write(pwd.join("init.lua"), config).unwrap();
+
+            run_binding(pwd, &Settings::default(), &"x".into(), "muxi").unwrap();
+
+            assert_eq!(std::fs::read_to_string(marker).unwrap(), "called");
+        });
+    }
+
+    #[test]
+    fn test_run_binding_without_function() {
+        let config = r#"
+            return {
+              bindings = {
+                x = { command = "tmux display hi" },
+              },
+            }
+        "#;
+
+        with_init_lua(config, |pwd| {
+            let missing = run_binding(pwd, &Settings::default(), &"y".into(), "muxi");
+            let not_lua = run_binding(pwd, &Settings::default(), &"x".into(), "muxi");
+
+            assert!(matches!(missing, Err(Error::BindingNotFound(key)) if key == "y"));
+            assert!(matches!(not_lua, Err(Error::BindingNotFound(key)) if key == "x"));
+        });
+    }
+
+    #[test]
+    fn test_run_binding_reports_lua_errors() {
+        let config = r#"
+            return {
+              bindings = {
+                x = { kind = "lua", command = function() error("boom") end },
+              },
+            }
+        "#;
+
+        with_init_lua(config, |pwd| {
+            let result = run_binding(pwd, &Settings::default(), &"x".into(), "muxi");
+
+            assert!(matches!(result, Err(Error::Binding { key, .. }) if key == "x"));
+        });
+    }
+
+    #[test]
+    fn test_run_binding_picks_table() {
+        let config = r#"
+            return {
+              bindings = {
+                x = {
+                  { kind = "lua", command = function() error("muxi table") end },
+                  { kind = "lua", table = "root", command = function() end },
+                },
+              },
+            }
+        "#;
+
+        with_init_lua(config, |pwd| {
+            let muxi = run_binding(pwd, &Settings::default(), &"x".into(), "muxi");
+            let root = run_binding(pwd, &Settings::default(), &"x".into(), "root");
+            let prefix = run_binding(pwd, &Settings::default(), &"x".into(), "prefix");
+
+            assert!(matches!(muxi, Err(Error::Binding { .. })));
+            assert!(root.is_ok());
+            assert!(matches!(prefix, Err(Error::BindingNotFound(key)) if key == "x"));
+        });
+    }
+}
@@ -12,6 +12,30 @@ pub enum Error {
     #[diagnostic(code(muxi::lua::runtime_error))]
     Lua(#[from] LuaError),
 
+    #[error("no Lua binding for {0}")]
+    #[diagnostic(
+        code(muxi::lua::binding_not_found),
+        help("Set `kind = \"lua\"` and a function as `command` in bindings[\"{0}\"]")
+    )]
+    BindingNotFound(String),
+
+    #[error("invalid binding for {key}: {reason}")]
+    #[diagnostic(
+        code(muxi::lua::invalid_binding),
+        help(
+            "Set a command string, or `kind = \"lua\"` and a function as `command` in bindings[\"{key}\"]"
+        )
+    )]
+    InvalidBinding { key: String, reason: String },
+
+    #[error("Lua binding {key} failed")]
+    #[diagnostic(code(muxi::lua::binding_error))]
+    Binding {
+        key: String,
+        #[source]
+        source: LuaError,
+    },
+
     #[error(transparent)]
     #[diagnostic(transparent)]
     LuaParse(#[from] Box<LuaParseDiagnostic>),
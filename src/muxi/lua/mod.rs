@@ -1,5 +1,7 @@
+mod binding;
 mod error;
 mod parse;
 
+pub use binding::run_binding;
 pub use error::Error;
 pub use parse::parse_settings;
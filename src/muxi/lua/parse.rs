@@ -2,15 +2,29 @@ use miette::{NamedSource, SourceSpan};
 use mlua::LuaSerdeExt;
 use mlua::Value as LuaValue;
 use mlua::prelude::{Lua, LuaError, LuaTable};
+use std::collections::HashSet;
 use std::path::Path;
 
-use crate::muxi::Settings;
 use crate::muxi::path;
+use crate::muxi::{BindingKind, Bindings, Settings};
 
 use super::error::LuaDeserializeDiagnostic;
 use super::error::{Error, LuaParseDiagnostic};
 
 pub fn parse_settings(path: &Path, settings: &Settings) -> Result<Settings, Error> {
+    let (_lua, muxi_config) = load_config(path, settings)?;
+    let functions = take_binding_functions(&muxi_config)?;
+
+    let settings =
+        deserialize_settings(LuaValue::Table(muxi_config)).map_err(enrich_deserialize_error)?;
+    validate_bindings(&settings.bindings, &functions)?;
+
+    Ok(settings)
+}
+
+/// Evaluates `init.lua` and merges the table it returns into `muxi.config`
+/// The `Lua` state has to outlive the returned table
+pub(super) fn load_config(path: &Path, settings: &Settings) -> Result<(Lua, LuaTable), Error> {
     let lua = lua_init(path, settings)?;
 
     let init_path = path.join("init.lua");
@@ -29,7 +43,7 @@ pub fn parse_settings(path: &Path, settings: &Settings) -> Result<Settings, Erro
         .exec()?;
     let muxi_config = lua.globals().get::<LuaTable>("muxi")?.get("config")?;
 
-    deserialize_settings(LuaValue::Table(muxi_config)).map_err(enrich_deserialize_error)
+    Ok((lua, muxi_config))
 }
 
 fn lua_init(path: &Path, settings: &Settings) -> Result<Lua, Error> {
@@ -63,6 +77,71 @@ fn lua_init(path: &Path, settings: &Settings) -> Result<Lua, Error> {
     Ok(lua)
 }
 
+/// Removes the functions set as `command` of Lua bindings, which can't be deserialized
+/// Returns the key and position of each binding that held one
+fn take_binding_functions(muxi_config: &LuaTable) -> Result<HashSet<(String, usize)>, Error> {
+    let mut functions = HashSet::new();
+
+    let Ok(Some(bindings)) = muxi_config.get::<Option<LuaTable>>("bindings") else {
+        return Ok(functions);
+    };
+
+    for pair in bindings.pairs::<LuaValue, LuaValue>() {
+        let (LuaValue::String(key), LuaValue::Table(key_bindings)) = pair? else {
+            continue;
+        };
+
+        // A key holds either a single binding or a list of them
+        let key_bindings = if key_bindings.raw_len() > 0 {
+            key_bindings
+                .sequence_values::<LuaValue>()
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![LuaValue::Table(key_bindings)]
+        };
+
+        for (index, binding) in key_bindings.into_iter().enumerate() {
+            if let LuaValue::Table(binding) = binding
+                && let LuaValue::Function(_) = binding.get::<LuaValue>("command")?
+            {
+                binding.set("command", LuaValue::Nil)?;
+                functions.insert((key.to_str()?.to_string(), index));
+            }
+        }
+    }
+
+    Ok(functions)
+}
+
+/// Checks that Lua bindings, and only them, had a function as `command`
+fn validate_bindings(
+    bindings: &Bindings,
+    functions: &HashSet<(String, usize)>,
+) -> Result<(), Error> {
+    for (key, key_bindings) in bindings {
+        for (index, binding) in key_bindings.iter().enumerate() {
+            let has_function = functions.contains(&(key.to_string(), index));
+
+            let reason = match binding.kind() {
+                BindingKind::Lua if !has_function => {
+                    "`kind = \"lua\"` needs a function as `command`"
+                }
+                BindingKind::Lua => continue,
+                _ if has_function => "a function as `command` needs `kind = \"lua\"`",
+                _ if binding.command.is_empty() => "`command` is missing",
+                _ => continue,
+            };
+
+            return Err(Error::InvalidBinding {
+                key: key.to_string(),
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 fn deserialize_settings(value: LuaValue) -> Result<Settings, serde_path_to_error::Error<LuaError>> {
     let deserializer = mlua::serde::Deserializer::new(value);
     serde_path_to_error::deserialize(deserializer)
@@ -166,8 +245,12 @@ mod tests {
     use uuid::Uuid;
 
     use crate::muxi::lua::Error;
-    use crate::muxi::{Binding, Bindings, EditorSettings, FzfSettings, Settings};
-    use crate::tmux::Popup;
+    use crate::muxi::{
+        Binding, BindingKind, Bindings, EditorSettings, FzfSettings, GitSettings, OptionsSettings,
+        ProjectsSettings, SessionSort, Settings,
+    };
+    use crate::tmux::OptionValue;
+    use crate::tmux::{CloseOnExit, Popup};
 
     use super::parse_settings;
 
@@ -229,10 +312,14 @@ mod tests {
                 muxi_prefix: "M-Space".into(),
                 uppercase_overrides: true,
                 use_current_pane_path: false,
+                session_sort: SessionSort::Key,
                 plugins: vec![],
+                git: GitSettings::default(),
                 editor: EditorSettings::default(),
                 fzf: FzfSettings::default(),
                 bindings: BTreeMap::new(),
+                options: OptionsSettings::default(),
+                projects: ProjectsSettings::default(),
             };
 
             assert_eq!(settings, expected_settings);
@@ -343,10 +430,11 @@ mod tests {
             let mut bindings: Bindings = BTreeMap::new();
             bindings.insert(
                 "j".into(),
-                Binding {
+                vec![Binding {
                     command: "tmux switch-client -l".into(),
                     popup: None,
-                },
+                    ..Default::default()
+                }],
             );
 
             let expected_settings = Settings {
@@ -372,14 +460,16 @@ mod tests {
             let mut bindings: Bindings = BTreeMap::new();
             bindings.insert(
                 "j".into(),
-                Binding {
+                vec![Binding {
                     command: "muxi sessions edit".into(),
                     popup: Some(Popup {
                         title: None,
                         width: "60%".into(),
                         height: "75%".into(),
+                        ..Default::default()
                     }),
-                },
+                    ..Default::default()
+                }],
             );
 
             let expected_settings = Settings {
@@ -405,14 +495,16 @@ mod tests {
             let mut bindings: Bindings = BTreeMap::new();
             bindings.insert(
                 "j".into(),
-                Binding {
+                vec![Binding {
                     command: "muxi sessions edit".into(),
                     popup: Some(Popup {
                         title: None,
                         width: "75%".into(),
                         height: "75%".into(),
+                        ..Default::default()
                     }),
-                },
+                    ..Default::default()
+                }],
             );
 
             let expected_settings = Settings {
@@ -442,14 +534,16 @@ mod tests {
             let mut bindings: Bindings = BTreeMap::new();
             bindings.insert(
                 "j".into(),
-                Binding {
+                vec![Binding {
                     command: "muxi sessions edit".into(),
                     popup: Some(Popup {
                         title: Some("my title".into()),
                         width: "75%".into(),
                         height: "60%".into(),
+                        ..Default::default()
                     }),
-                },
+                    ..Default::default()
+                }],
             );
 
             let expected_settings = Settings {
@@ -462,6 +556,261 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_parse_git_settings() {
+        let config = r"
+            muxi.config.git.jobs = 2
+            muxi.config.git.timeout = 0
+        ";
+
+        with_config(config, |settings| {
+            assert_eq!(
+                settings.git,
+                GitSettings {
+                    jobs: 2,
+                    timeout: 0,
+                    retries: 2,
+                    depth: None,
+                    filter: None,
+                }
+            );
+            assert_eq!(settings.git.timeout(), None);
+        });
+    }
+
+    #[test]
+    fn test_parse_projects() {
+        let config = r#"
+            muxi.config.projects = {
+              roots = { "~/code", "/srv/work" },
+              max_depth = 3,
+              ignore = { "node_modules", ".*" },
+            }
+        "#;
+
+        with_config(config, |settings| {
+            assert_eq!(
+                settings.projects,
+                ProjectsSettings {
+                    roots: vec!["~/code".into(), "/srv/work".into()],
+                    max_depth: 3,
+                    markers: vec![".git".into()],
+                    ignore: vec!["node_modules".into(), ".*".into()],
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let config = r#"
+            return {
+              options = {
+                global = { mouse = true, ["status-position"] = "top" },
+                server = { ["escape-time"] = 0 },
+                window = { ["pane-base-index"] = 1 },
+              },
+            }
+        "#;
+
+        with_config(config, |settings| {
+            let options = settings
+                .options
+                .iter()
+                .map(|(scope, name, value)| (scope.to_string(), name.clone(), value.clone()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                options,
+                vec![
+                    (
+                        "server".into(),
+                        "escape-time".into(),
+                        OptionValue::Integer(0)
+                    ),
+                    ("global".into(), "mouse".into(), OptionValue::Bool(true)),
+                    (
+                        "global".into(),
+                        "status-position".into(),
+                        OptionValue::String("top".into())
+                    ),
+                    (
+                        "window".into(),
+                        "pane-base-index".into(),
+                        OptionValue::Integer(1)
+                    ),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_binding_table_repeat_and_note() {
+        let config = r#"
+            muxi.config.bindings = {
+              ["C-h"] = {
+                command = "select-pane -L",
+                table = "root",
+                repeatable = true,
+                note = "Select left pane",
+              },
+            }
+        "#;
+
+        with_config(config, |settings| {
+            let binding = &settings.bindings[&"C-h".into()][0];
+
+            assert_eq!(
+                binding,
+                &Binding {
+                    command: "select-pane -L".into(),
+                    table: "root".into(),
+                    repeatable: true,
+                    note: Some("Select left pane".into()),
+                    ..Default::default()
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_key_bound_in_two_tables() {
+        let config = r#"
+            muxi.config.bindings = {
+              h = {
+                { command = "muxi sessions switch h" },
+                { table = "copy-mode-vi", kind = "tmux", command = "send -X cursor-left" },
+              },
+            }
+        "#;
+
+        with_config(config, |settings| {
+            assert_eq!(
+                settings.bindings[&"h".into()],
+                vec![
+                    Binding {
+                        command: "muxi sessions switch h".into(),
+                        ..Default::default()
+                    },
+                    Binding {
+                        command: "send -X cursor-left".into(),
+                        kind: Some(BindingKind::Tmux),
+                        table: "copy-mode-vi".into(),
+                        ..Default::default()
+                    },
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_popup_options() {
+        let config = r#"
+            return {
+              bindings = {
+                h = {
+                  command = "htop",
+                  popup = {
+                    x = "R",
+                    border = "double",
+                    border_style = "fg=blue",
+                    env = { FOO = "bar" },
+                    close_on_exit = "never",
+                  },
+                },
+              },
+            }
+        "#;
+
+        with_config(config, |settings| {
+            let popup = settings.bindings[&"h".into()][0].popup.as_ref().unwrap();
+
+            assert_eq!(
+                popup,
+                &Popup {
+                    x: Some("R".into()),
+                    border: "double".into(),
+                    border_style: Some("fg=blue".into()),
+                    env: BTreeMap::from([("FOO".into(), "bar".into())]),
+                    close_on_exit: CloseOnExit::Never,
+                    ..Default::default()
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse_session_sort() {
+        with_config(r#"return { session_sort = "mru" }"#, |settings| {
+            assert_eq!(settings.session_sort, SessionSort::Mru);
+        });
+    }
+
+    #[test]
+    fn test_parse_binding_kinds() {
+        let config = r#"
+            return {
+              bindings = {
+                v = { kind = "tmux", command = "split-window -h" },
+                l = { kind = "lua", command = function() end },
+                r = {
+                  { command = "htop" },
+                  { table = "root", kind = "lua", command = function() end },
+                },
+              },
+            }
+        "#;
+
+        with_config(config, |settings| {
+            let tmux = &settings.bindings[&"v".into()][0];
+            let lua = &settings.bindings[&"l".into()][0];
+            let root_lua = &settings.bindings[&"r".into()][1];
+
+            assert_eq!(tmux.kind(), BindingKind::Tmux);
+            assert_eq!(tmux.command, "split-window -h");
+            assert_eq!(lua.kind(), BindingKind::Lua);
+            assert_eq!(lua.command, "");
+            assert_eq!(root_lua.kind(), BindingKind::Lua);
+        });
+    }
+
+    #[test]
+    fn test_parse_invalid_bindings() {
+        for (binding, reason) in [
+            (r#"{ cmd = "htop" }"#, "`command` is missing"),
+            (
+                "{ command = function() end }",
+                "a function as `command` needs `kind = \"lua\"`",
+            ),
+            (
+                r#"{ kind = "lua", command = "htop" }"#,
+                "`kind = \"lua\"` needs a function as `command`",
+            ),
+        ] {
+            let config = format!("return {{ bindings = {{ h = {binding} }} }}");
+
+            with_config_error(&config, |error| match error {
+                Error::InvalidBinding {
+                    key,
+                    reason: actual,
+                } => {
+                    assert_eq!(key, "h");
+                    assert_eq!(actual, reason);
+                }
+                other => panic!("expected InvalidBinding error, got {other:?}"),
+            });
+        }
+    }
+
+    #[test]
+    fn test_parse_function_outside_bindings_fails() {
+        let config = "return { editor = { command = function() end } }";
+
+        with_config_error(config, |error| {
+            assert!(matches!(error, Error::LuaDeserialize(_)), "{error:?}");
+        });
+    }
+
     #[test]
     fn test_parse_plugins() {
         let config = r#"
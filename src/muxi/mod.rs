@@ -4,9 +4,15 @@ pub mod sessions;
 
 mod model;
 mod plugin;
+mod projects;
+mod session_history;
 mod settings;
+mod template;
 
 pub use model::*;
 pub use plugin::*;
+pub use projects::*;
+pub use session_history::*;
 pub use sessions::*;
 pub use settings::*;
+pub use template::*;
@@ -1,9 +1,18 @@
+use std::collections::BTreeMap;
+
 use miette::Diagnostic;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{Sessions, path};
+use crate::tmux::Key;
+
+use super::{Session, Sessions, Template, path};
+
+/// Key of the templates table in sessions.toml
+pub const TEMPLATES_KEY: &str = "templates";
 
 #[derive(Debug, Error, Diagnostic)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[error("Error reading your sessions file")]
     #[diagnostic(
@@ -20,6 +29,27 @@ pub enum Error {
         )
     )]
     ParseError(#[from] toml_edit::de::Error),
+
+    #[error("Session {0} uses the unknown template {1}")]
+    #[diagnostic(
+        code(muxi::sessions::unknown_template),
+        help("Define it in ~/.config/muxi/sessions.toml:\n[templates.{1}]\non_create = [...]")
+    )]
+    UnknownTemplate(Key, String),
+
+    #[error("{0} can't be used as a session key")]
+    #[diagnostic(
+        code(muxi::sessions::reserved_key),
+        help(
+            "`templates` holds the session templates in ~/.config/muxi/sessions.toml, bookmark the session under another key"
+        )
+    )]
+    ReservedKey(Key),
+}
+
+/// Keys that can't hold a session bookmark
+pub fn is_reserved_key(key: &Key) -> bool {
+    key.as_ref() == TEMPLATES_KEY
 }
 
 #[derive(Debug)]
@@ -29,6 +59,23 @@ pub struct Muxi {
 
 impl Muxi {
     pub fn new() -> Result<Self, Error> {
+        let sessions = SessionsFile::read()?.sessions()?;
+
+        Ok(Self { sessions })
+    }
+}
+
+/// Contents of sessions.toml, before applying the templates
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(super) struct SessionsFile {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    templates: BTreeMap<String, Template>,
+    #[serde(flatten)]
+    sessions: BTreeMap<Key, Session>,
+}
+
+impl SessionsFile {
+    pub(super) fn read() -> Result<Self, Error> {
         let sessions_file = path::sessions_file();
 
         if std::fs::metadata(&sessions_file).is_err() {
@@ -37,14 +84,93 @@ impl Muxi {
         }
 
         let sessions_data = std::fs::read_to_string(sessions_file)?;
-        let sessions = Sessions(toml_edit::de::from_str(&sessions_data)?);
 
-        Ok(Self { sessions })
+        if has_templates_session(&sessions_data) {
+            return Err(Error::ReservedKey(TEMPLATES_KEY.into()));
+        }
+
+        Ok(toml_edit::de::from_str(&sessions_data)?)
+    }
+
+    /// Sessions with their templates applied
+    pub(super) fn sessions(&self) -> Result<Sessions, Error> {
+        self.sessions
+            .iter()
+            .map(|(key, session)| Ok((key.clone(), self.apply_template(key, session)?)))
+            .collect::<Result<_, _>>()
+            .map(Sessions)
+    }
+
+    /// Replaces the sessions, keeping the templates
+    /// Sessions unchanged since they were read are kept as written, so they
+    /// still use their template instead of a copy of it
+    pub(super) fn with_sessions(self, sessions: &Sessions) -> Self {
+        let sessions = sessions
+            .0
+            .iter()
+            .map(|(key, session)| {
+                let written = self.sessions.get(key).filter(|written| {
+                    self.apply_template(key, written).as_ref().ok() == Some(session)
+                });
+
+                (key.clone(), written.unwrap_or(session).clone())
+            })
+            .collect();
+
+        Self { sessions, ..self }
+    }
+
+    /// Sessions stay inline tables, and templates get a `[templates.<name>]` table each
+    pub(super) fn to_toml(&self) -> Result<String, toml_edit::ser::Error> {
+        let mut document = toml_edit::ser::to_document(self)?;
+
+        if let Some(templates) = document.get_mut("templates")
+            && let Ok(mut table) = templates.clone().into_table()
+        {
+            table.set_implicit(true);
+
+            for (_, template) in table.iter_mut() {
+                if let Ok(template_table) = template.clone().into_table() {
+                    *template = toml_edit::Item::Table(template_table);
+                }
+            }
+
+            *templates = toml_edit::Item::Table(table);
+        }
+
+        Ok(document.to_string())
+    }
+
+    fn apply_template(&self, key: &Key, session: &Session) -> Result<Session, Error> {
+        let Some(name) = &session.template else {
+            return Ok(session.clone());
+        };
+
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| Error::UnknownTemplate(key.clone(), name.clone()))?;
+
+        Ok(template.apply(session))
     }
 }
 
+/// A session bookmarked under `templates` would be read as the templates table,
+/// which it tells apart by holding plain values where templates hold tables
+fn has_templates_session(sessions_data: &str) -> bool {
+    let Ok(document) = sessions_data.parse::<toml_edit::DocumentMut>() else {
+        return false;
+    };
+
+    document
+        .get(TEMPLATES_KEY)
+        .and_then(toml_edit::Item::as_table_like)
+        .is_some_and(|templates| templates.iter().any(|(_, item)| !item.is_table_like()))
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::env::temp_dir;
     use std::io::Write;
     use std::path::PathBuf;
@@ -87,7 +213,7 @@ mod tests {
                     Session {
                         name: "dotfiles".into(),
                         path: path::expand_tilde("~/.dotfiles".into()),
-                        on_create: Vec::new(),
+                        ..Default::default()
                     },
                 ),
                 (
@@ -95,7 +221,7 @@ mod tests {
                     Session {
                         name: "muxi".into(),
                         path: path::expand_tilde("/home/user/muxi/".into()),
-                        on_create: Vec::new(),
+                        ..Default::default()
                     },
                 ),
                 (
@@ -103,7 +229,7 @@ mod tests {
                     Session {
                         name: "tmux".into(),
                         path: path::expand_tilde("~/Sites/tmux/".into()),
-                        on_create: Vec::new(),
+                        ..Default::default()
                     },
                 ),
                 (
@@ -113,7 +239,7 @@ mod tests {
                         path: path::expand_tilde(
                             "~/Library/Mobile Documents/com~apple~CloudDocs/notes".into(),
                         ),
-                        on_create: Vec::new(),
+                        ..Default::default()
                     },
                 ),
             ]
@@ -156,7 +282,9 @@ mod tests {
                         path: Some(PathBuf::from("../qmk_firmware")),
                         name: Some("firmware".into()),
                         command: None,
+                        env: BTreeMap::new(),
                     })],
+                    ..Default::default()
                 },
             )]
             .into_iter()
@@ -167,4 +295,114 @@ mod tests {
             assert_eq!(sessions, expected_sessions);
         });
     }
+
+    #[test]
+    fn test_sessions_with_template() {
+        let config = r#"
+            [templates.rust]
+            window_name = "editor"
+            on_create = [{ new_window = { path = "src", name = "src" } }]
+            env = { RUST_BACKTRACE = "1" }
+
+            [m]
+            name = "muxi"
+            path = "/code/muxi"
+            template = "rust"
+        "#;
+
+        with_config(config, |sessions| {
+            assert_eq!(
+                sessions.0[&Key::new("m")],
+                Session {
+                    name: "muxi".into(),
+                    path: "/code/muxi".into(),
+                    template: Some("rust".into()),
+                    window_name: Some("editor".into()),
+                    on_create: vec![OnCreateAction::NewWindow(NewWindow {
+                        path: Some("src".into()),
+                        name: Some("src".into()),
+                        ..Default::default()
+                    })],
+                    env: BTreeMap::from([("RUST_BACKTRACE".into(), "1".into())]),
+                    ..Default::default()
+                }
+            );
+        });
+    }
+
+    #[test]
+    fn test_sessions_with_unknown_template() {
+        let pwd = tempfile::tempdir().unwrap();
+        std::fs::write(
+            pwd.path().join("sessions.toml"),
+            r#"m = { name = "muxi", path = "/code/muxi", template = "rust" }"#,
+        )
+        .unwrap();
+
+        temp_env::with_var("MUXI_CONFIG_PATH", Some(pwd.path()), || {
+            let muxi = Muxi::new();
+
+            assert!(
+                matches!(muxi, Err(Error::UnknownTemplate(key, template)) if key.as_ref() == "m" && template == "rust")
+            );
+        });
+    }
+
+    #[test]
+    fn test_save_keeps_templates() {
+        let pwd = tempfile::tempdir().unwrap();
+        std::fs::write(
+            pwd.path().join("sessions.toml"),
+            r#"
+                [templates.rust]
+                on_create = [{ new_window = { name = "shell" } }]
+
+                [m]
+                name = "muxi"
+                path = "/code/muxi"
+                template = "rust"
+            "#,
+        )
+        .unwrap();
+
+        temp_env::with_var("MUXI_CONFIG_PATH", Some(pwd.path()), || {
+            let mut sessions = Muxi::new().unwrap().sessions;
+            sessions.0.insert(
+                "d".into(),
+                Session {
+                    name: "dotfiles".into(),
+                    path: "/code/dotfiles".into(),
+                    ..Default::default()
+                },
+            );
+            sessions.save().unwrap();
+
+            let toml = std::fs::read_to_string(pwd.path().join("sessions.toml"));
+            let file = SessionsFile::read();
+            let reloaded = Muxi::new();
+
+            assert!(toml.unwrap().contains("[templates.rust]"));
+
+            let file = file.unwrap();
+            assert!(file.templates.contains_key("rust"));
+            assert_eq!(file.sessions[&Key::new("m")].on_create, vec![]);
+            assert_eq!(reloaded.unwrap().sessions, sessions);
+        });
+    }
+
+    #[test]
+    fn test_sessions_with_templates_key() {
+        let pwd = tempfile::tempdir().unwrap();
+        std::fs::write(
+            pwd.path().join("sessions.toml"),
+            r#"templates = { name = "templates", path = "/code/templates" }"#,
+        )
+        .unwrap();
+
+        temp_env::with_var("MUXI_CONFIG_PATH", Some(pwd.path()), || {
+            let muxi = Muxi::new();
+
+            assert!(matches!(muxi, Err(Error::ReservedKey(key)) if key.as_ref() == "templates"));
+        });
+    }
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn muxi_dir() -> PathBuf {
     let path = if let Ok(env_path) = std::env::var("MUXI_CONFIG_PATH") {
@@ -28,6 +28,18 @@ pub fn plugins_dir() -> PathBuf {
     muxi_data().join("plugins")
 }
 
+pub fn plugins_history_dir() -> PathBuf {
+    muxi_data().join("history").join("plugins")
+}
+
+pub fn session_history_file() -> PathBuf {
+    muxi_data().join("history").join("sessions")
+}
+
+pub fn applied_options_file() -> PathBuf {
+    muxi_data().join("applied_options.toml")
+}
+
 pub fn settings_file() -> PathBuf {
     muxi_dir().join("init.lua")
 }
@@ -48,6 +60,25 @@ pub fn sessions_file() -> PathBuf {
     muxi_dir().join("sessions.toml")
 }
 
+/// The tmux config file, looked up the same way tmux does
+pub fn tmux_conf_file() -> PathBuf {
+    let home_conf = expand_tilde("~/.tmux.conf".into());
+
+    if home_conf.exists() {
+        return home_conf;
+    }
+
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map_or_else(|_| expand_tilde("~/.config".into()), PathBuf::from);
+
+    config_home.join("tmux/tmux.conf")
+}
+
+/// Where TPM clones plugins by default
+pub fn tpm_plugins_dir() -> PathBuf {
+    expand_tilde("~/.tmux/plugins".into())
+}
+
 pub fn expand_tilde(path: PathBuf) -> PathBuf {
     if !path.starts_with("~") {
         return path;
@@ -59,6 +90,45 @@ pub fn expand_tilde(path: PathBuf) -> PathBuf {
     home_dir.join(relative_path)
 }
 
+/// Expands `${VAR}` from the environment, unset variables expand to nothing,
+/// and a leading `~`
+pub fn expand_vars(value: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&std::env::var(&rest[start + 2..start + end]).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+
+    expanded.push_str(rest);
+
+    match expanded.strip_prefix('~') {
+        Some(path) if path.is_empty() || path.starts_with('/') => {
+            let home_dir = dirs::home_dir().unwrap();
+            format!("{}{path}", home_dir.display())
+        }
+        _ => expanded,
+    }
+}
+
+/// Whether both paths are the same directory once `~` and `${VAR}` are
+/// expanded and symlinks resolved, falling back to the expanded paths if
+/// they don't exist
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    let resolve = |path: &Path| {
+        let path = PathBuf::from(expand_vars(&path.to_string_lossy()));
+        path.canonicalize().unwrap_or(path)
+    };
+
+    resolve(a) == resolve(b)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +197,45 @@ mod tests {
 
         assert_eq!(expanded_path, PathBuf::from("/some/path"));
     }
+
+    #[test]
+    fn test_expand_vars() {
+        let home_dir = dirs::home_dir().unwrap();
+
+        temp_env::with_vars(
+            [
+                ("MUXI_TEST_PROFILE", Some("work")),
+                ("MUXI_TEST_UNSET", None),
+            ],
+            || {
+                assert_eq!(expand_vars("${MUXI_TEST_PROFILE}-dev"), "work-dev");
+                assert_eq!(expand_vars("a${MUXI_TEST_UNSET}b"), "ab");
+                assert_eq!(expand_vars("${MUXI_TEST_PROFILE"), "${MUXI_TEST_PROFILE");
+                assert_eq!(
+                    expand_vars("~/.aws/${MUXI_TEST_PROFILE}"),
+                    format!("{}/.aws/work", home_dir.display())
+                );
+                assert_eq!(expand_vars("~"), home_dir.display().to_string());
+                assert_eq!(expand_vars("a~b"), "a~b");
+                assert_eq!(expand_vars("~user"), "~user");
+            },
+        );
+    }
+
+    #[test]
+    fn test_same_path() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("muxi")).unwrap();
+        std::os::unix::fs::symlink(dir.join("muxi"), dir.join("link")).unwrap();
+
+        assert!(same_path(&dir.join("muxi/"), &dir.join("muxi")));
+        assert!(same_path(&dir.join("link"), &dir.join("muxi")));
+        assert!(same_path(&dir.join("muxi/../muxi"), &dir.join("muxi")));
+        assert!(!same_path(&dir.join("link"), dir));
+        assert!(same_path(
+            Path::new("~/code/muxi"),
+            &dirs::home_dir().unwrap().join("code/muxi")
+        ));
+    }
 }
@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use miette::{IntoDiagnostic, Result};
+
+use crate::muxi::path;
+
+use super::Plugin;
+use super::install::install_path;
+use super::shared::git;
+
+/// Maximum number of revisions kept per plugin
+const HISTORY_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginRevision {
+    pub id: String,
+    pub full_id: String,
+    pub summary: String,
+    pub time: SystemTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct HistoryEntry {
+    pub commit: String,
+    pub time: SystemTime,
+}
+
+impl Plugin {
+    /// Revisions this plugin was at before each update, newest first
+    pub fn history(&self) -> Result<Vec<PluginRevision>> {
+        if self.path.is_some() || !self.is_installed() {
+            return Ok(Vec::new());
+        }
+
+        let dir = install_path(self);
+
+        read_history(self)?
+            .into_iter()
+            .rev()
+            .map(|entry| {
+                let output = git(&["log", "-1", "--format=%h%x00%s", &entry.commit], &dir)?;
+                let (id, summary) = output.split_once('\0').unwrap_or((&output, ""));
+
+                Ok(PluginRevision {
+                    id: id.to_string(),
+                    full_id: entry.commit,
+                    summary: summary.to_string(),
+                    time: entry.time,
+                })
+            })
+            .collect()
+    }
+}
+
+pub(super) fn history_file(plugin: &Plugin) -> PathBuf {
+    path::plugins_history_dir().join(&plugin.name)
+}
+
+/// Reads the recorded revisions of a plugin, oldest first
+pub(super) fn read_history(plugin: &Plugin) -> Result<Vec<HistoryEntry>> {
+    let file = history_file(plugin);
+
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(&file).into_diagnostic()?;
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (commit, timestamp) = line
+                .split_once(' ')
+                .ok_or_else(|| miette::miette!("Failed to parse plugin history: {line}"))?;
+
+            let timestamp: u64 = timestamp
+                .trim()
+                .parse()
+                .map_err(|_| miette::miette!("Failed to parse history timestamp: {timestamp}"))?;
+
+            Ok(HistoryEntry {
+                commit: commit.to_string(),
+                time: SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp),
+            })
+        })
+        .collect()
+}
+
+/// Overwrites the recorded revisions of a plugin, keeping the latest `HISTORY_LIMIT`
+pub(super) fn write_history(plugin: &Plugin, entries: &[HistoryEntry]) -> Result<()> {
+    let file = history_file(plugin);
+    let start = entries.len().saturating_sub(HISTORY_LIMIT);
+
+    std::fs::create_dir_all(path::plugins_history_dir()).into_diagnostic()?;
+
+    let contents = entries[start..]
+        .iter()
+        .map(|entry| {
+            let timestamp = entry
+                .time
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+
+            format!("{} {timestamp}\n", entry.commit)
+        })
+        .collect::<String>();
+
+    std::fs::write(file, contents).into_diagnostic()
+}
+
+/// Appends `commit` to the plugin's history
+pub(super) fn record_revision(plugin: &Plugin, commit: &str) -> Result<()> {
+    let mut entries = read_history(plugin)?;
+
+    entries.push(HistoryEntry {
+        commit: commit.to_string(),
+        time: SystemTime::now(),
+    });
+
+    write_history(plugin, &entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path()), || {
+            let plugin = Plugin::parse("tmux-plugins/tmux-continuum").unwrap();
+
+            assert!(read_history(&plugin).unwrap().is_empty());
+
+            record_revision(&plugin, "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+            record_revision(&plugin, "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap();
+
+            let commits = read_history(&plugin)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.commit)
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                commits,
+                vec![
+                    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                    "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let temp = tempfile::tempdir().unwrap();
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path()), || {
+            let plugin = Plugin::parse("tmux-plugins/tmux-continuum").unwrap();
+            let entries = (0..HISTORY_LIMIT + 5)
+                .map(|index| HistoryEntry {
+                    commit: format!("{index:040}"),
+                    time: SystemTime::UNIX_EPOCH,
+                })
+                .collect::<Vec<_>>();
+
+            write_history(&plugin, &entries).unwrap();
+            let history = read_history(&plugin).unwrap();
+
+            assert_eq!(history.len(), HISTORY_LIMIT);
+            assert_eq!(history[0].commit, format!("{:040}", 5));
+        });
+    }
+}
@@ -0,0 +1,512 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use miette::{IntoDiagnostic, Result};
+
+use crate::muxi::path;
+
+use super::Plugin;
+use super::install::install_path;
+
+/// Plugins TPM manages itself, no need to import them
+const TPM_PLUGINS: &[&str] = &["tmux-plugins/tpm"];
+
+/// A TPM `@plugin` entry with the `@` options that belong to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedPlugin {
+    /// The plugin as written in tmux.conf (e.g. `tmux-plugins/tmux-yank`)
+    pub spec: String,
+    /// Branch requested with `owner/repo#branch`, which muxi doesn't support
+    pub branch: Option<String>,
+    pub plugin: Plugin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TpmImport {
+    pub plugins: Vec<ImportedPlugin>,
+    /// `@` options that couldn't be matched to any plugin
+    pub unmatched_options: Vec<(String, String)>,
+}
+
+impl TpmImport {
+    /// Parses the `set -g @plugin` and `set -g @<option>` lines of a tmux config
+    pub fn parse(tmux_conf: &str) -> Self {
+        let mut import = Self::default();
+        let mut options = Vec::new();
+
+        for line in tmux_conf.lines() {
+            let Some((key, value)) = parse_user_option(line) else {
+                continue;
+            };
+
+            if key == "plugin" {
+                if let Some(plugin) = parse_plugin(&value) {
+                    import.plugins.push(plugin);
+                }
+            } else {
+                options.push((key, value));
+            }
+        }
+
+        for (key, value) in options {
+            match import
+                .plugins
+                .iter_mut()
+                .find(|imported| owns_option(&imported.plugin, &key))
+            {
+                Some(imported) => {
+                    imported.plugin.options.insert(key, value.into());
+                }
+                None => import.unmatched_options.push((key, value)),
+            }
+        }
+
+        import
+    }
+
+    /// Drops the plugins whose name is already in `plugins`
+    pub fn without(mut self, plugins: &[Plugin]) -> Self {
+        self.plugins.retain(|imported| {
+            !plugins
+                .iter()
+                .any(|plugin| plugin.name == imported.plugin.name)
+        });
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty() && self.unmatched_options.is_empty()
+    }
+
+    /// Renders the plugins as entries of the `plugins` table in init.lua
+    pub fn to_lua(&self, indent: &str) -> String {
+        let mut lua = String::new();
+
+        for imported in &self.plugins {
+            if let Some(branch) = &imported.branch {
+                let _ = writeln!(
+                    lua,
+                    "{indent}-- TPM branch `{branch}` is not supported, muxi follows the default branch"
+                );
+            }
+
+            if imported.plugin.options.is_empty() {
+                let _ = writeln!(lua, "{indent}{},", lua_string(&imported.spec));
+                continue;
+            }
+
+            let _ = writeln!(lua, "{indent}{{");
+            let _ = writeln!(lua, "{indent}  url = {},", lua_string(&imported.spec));
+            let _ = writeln!(lua, "{indent}  opts = {{");
+
+            for (key, option) in &imported.plugin.options {
+                let value = option.value.to_string();
+                let _ = writeln!(
+                    lua,
+                    "{indent}    {} = {},",
+                    lua_key(key),
+                    lua_string(&value)
+                );
+            }
+
+            let _ = writeln!(lua, "{indent}  }},");
+            let _ = writeln!(lua, "{indent}}},");
+        }
+
+        for (key, value) in &self.unmatched_options {
+            let _ = writeln!(
+                lua,
+                "{indent}-- No plugin found for: set -g @{key} {}",
+                lua_string(value)
+            );
+        }
+
+        lua
+    }
+}
+
+impl Plugin {
+    /// Copies a clone of this plugin made by TPM under `tpm_dir` into the plugins directory
+    /// The TPM clone is left alone, so TPM keeps working until tmux.conf is changed
+    /// Returns `false` if there was nothing to adopt
+    pub fn adopt(&self, tpm_dir: &Path) -> Result<bool> {
+        let source = tpm_dir.join(&self.name);
+
+        if self.path.is_some() || self.is_installed() || !source.join(".git").exists() {
+            return Ok(false);
+        }
+
+        let target = install_path(self);
+
+        std::fs::create_dir_all(path::plugins_dir()).into_diagnostic()?;
+        copy_dir(&source, &target).map_err(|error| {
+            // Don't leave half a plugin behind
+            let _ = std::fs::remove_dir_all(&target);
+            miette::miette!("Failed to copy {}: {error}", source.display())
+        })?;
+
+        Ok(true)
+    }
+}
+
+/// Copies a directory recursively, keeping symlinks as symlinks
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::fs::create_dir(target)?;
+
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = target.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, target)?;
+        } else {
+            // Keeps the permissions, so scripts stay executable
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Inserts `entries` at the top of the `plugins = {` table of an init.lua
+/// Returns `None` if there's no such table
+pub fn insert_into_plugins_table(init_lua: &str, entries: &str) -> Option<String> {
+    let mut output = String::with_capacity(init_lua.len() + entries.len());
+    let mut inserted = false;
+
+    for line in init_lua.split_inclusive('\n') {
+        output.push_str(line);
+
+        if !inserted && line.trim() == "plugins = {" {
+            if !line.ends_with('\n') {
+                output.push('\n');
+            }
+
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let indented = entries
+                .lines()
+                .map(|entry| format!("{indent}  {entry}\n"))
+                .collect::<String>();
+
+            output.push_str(&indented);
+            inserted = true;
+        }
+    }
+
+    inserted.then_some(output)
+}
+
+/// Parses `set[-option] [-flags] @<key> <value>`
+fn parse_user_option(line: &str) -> Option<(String, String)> {
+    let words = split_words(line);
+    let mut words = words.iter().map(String::as_str);
+
+    if !matches!(words.next()?, "set" | "set-option") {
+        return None;
+    }
+
+    let mut word = words.next()?;
+    while word.starts_with('-') {
+        word = words.next()?;
+    }
+
+    let key = word.strip_prefix('@')?;
+    let value = words.next()?;
+
+    Some((key.to_string(), value.to_string()))
+}
+
+fn parse_plugin(value: &str) -> Option<ImportedPlugin> {
+    let (spec, branch) = match value.split_once('#') {
+        Some((spec, branch)) => (spec, Some(branch.to_string())),
+        None => (value, None),
+    };
+
+    if TPM_PLUGINS.contains(&spec) {
+        return None;
+    }
+
+    let plugin = Plugin::parse(spec).ok()?;
+
+    Some(ImportedPlugin {
+        spec: spec.to_string(),
+        branch,
+        plugin,
+    })
+}
+
+/// Whether `@key` looks like an option of `plugin`
+/// e.g. `@resurrect-strategy-nvim` belongs to `tmux-resurrect`
+fn owns_option(plugin: &Plugin, key: &str) -> bool {
+    option_prefixes(plugin).iter().any(|prefix| {
+        key.strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '_']))
+    })
+}
+
+fn option_prefixes(plugin: &Plugin) -> Vec<String> {
+    let name = plugin
+        .name
+        .trim_start_matches("tmux-")
+        .trim_end_matches("-tmux")
+        .trim_end_matches(".tmux");
+
+    let mut prefixes = vec![name.to_string()];
+
+    // Repos named just `tmux` (e.g. `catppuccin/tmux`) use their owner as prefix
+    if name == "tmux"
+        && let Some(owner) = plugin
+            .url
+            .as_ref()
+            .and_then(|url| url.path_segments()?.next().map(ToString::to_string))
+    {
+        prefixes.push(owner);
+    }
+
+    prefixes
+}
+
+/// Splits a tmux config line into words, honoring quotes and `#` comments
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '#') if !in_word => break,
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    words
+}
+
+fn lua_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn lua_key(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_identifier {
+        key.to_string()
+    } else {
+        format!("[{}]", lua_string(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TMUX_CONF: &str = r#"
+        # List of plugins
+        set -g @plugin 'tmux-plugins/tpm'
+        set -g @plugin 'tmux-plugins/tmux-sensible'
+        set -g @plugin "tmux-plugins/tmux-resurrect"
+        set-option -g @plugin 'tmux-plugins/tmux-yank#v2.3.0' # pinned
+        set -g @plugin 'catppuccin/tmux'
+
+        set -g @resurrect-strategy-nvim 'session'
+        set -g @yank_selection_mouse 'clipboard'
+        set -g @catppuccin_flavor "mocha"
+        set -g @unrelated on
+        set -g status-left ''
+
+        run '~/.tmux/plugins/tpm/tpm'
+    "#;
+
+    #[test]
+    fn test_parse_tpm_plugins() {
+        let import = TpmImport::parse(TMUX_CONF);
+
+        let specs = import
+            .plugins
+            .iter()
+            .map(|imported| imported.spec.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            specs,
+            vec![
+                "tmux-plugins/tmux-sensible",
+                "tmux-plugins/tmux-resurrect",
+                "tmux-plugins/tmux-yank",
+                "catppuccin/tmux",
+            ]
+        );
+        assert_eq!(import.plugins[2].branch.as_deref(), Some("v2.3.0"));
+    }
+
+    #[test]
+    fn test_parse_tpm_options() {
+        let import = TpmImport::parse(TMUX_CONF);
+
+        assert!(import.plugins[0].plugin.options.is_empty());
+        assert_eq!(
+            import.plugins[1]
+                .plugin
+                .options
+                .get("resurrect-strategy-nvim"),
+            Some(&"session".into())
+        );
+        assert_eq!(
+            import.plugins[2].plugin.options.get("yank_selection_mouse"),
+            Some(&"clipboard".into())
+        );
+        assert_eq!(
+            import.plugins[3].plugin.options.get("catppuccin_flavor"),
+            Some(&"mocha".into())
+        );
+        assert_eq!(
+            import.unmatched_options,
+            vec![("unrelated".to_string(), "on".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_without_configured_plugins() {
+        let configured = vec![Plugin::parse("tmux-plugins/tmux-sensible").unwrap()];
+        let import = TpmImport::parse(TMUX_CONF).without(&configured);
+
+        assert_eq!(import.plugins.len(), 3);
+        assert_eq!(import.plugins[0].spec, "tmux-plugins/tmux-resurrect");
+    }
+
+    #[test]
+    fn test_render_lua() {
+        let import = TpmImport::parse(
+            r"
+            set -g @plugin 'tmux-plugins/tmux-sensible'
+            set -g @plugin 'tmux-plugins/tmux-resurrect'
+            set -g @resurrect-strategy-nvim 'session'
+            set -g @unrelated 'on'
+            ",
+        );
+
+        assert_eq!(
+            import.to_lua(""),
+            r#""tmux-plugins/tmux-sensible",
+{
+  url = "tmux-plugins/tmux-resurrect",
+  opts = {
+    ["resurrect-strategy-nvim"] = "session",
+  },
+},
+-- No plugin found for: set -g @unrelated "on"
+"#
+        );
+    }
+
+    #[test]
+    fn test_insert_into_plugins_table() {
+        let init_lua = "return {\n  plugins = {\n    \"a/b\",\n  },\n}\n";
+        let output = insert_into_plugins_table(init_lua, "\"c/d\",\n").unwrap();
+
+        assert_eq!(
+            output,
+            "return {\n  plugins = {\n    \"c/d\",\n    \"a/b\",\n  },\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_without_plugins_table() {
+        assert!(insert_into_plugins_table("return {}\n", "\"c/d\",\n").is_none());
+    }
+
+    #[test]
+    fn test_adopt_copies_tpm_clone() {
+        let temp = tempfile::tempdir().unwrap();
+        let tpm_dir = temp.path().join("tpm");
+        std::fs::create_dir_all(tpm_dir.join("tmux-yank/.git")).unwrap();
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path().join("data")), || {
+            let plugin = Plugin::parse("tmux-plugins/tmux-yank").unwrap();
+            let missing = Plugin::parse("tmux-plugins/tmux-sensible").unwrap();
+
+            let adopted = plugin.adopt(&tpm_dir).unwrap();
+            let installed = plugin.is_installed();
+            let adopted_missing = missing.adopt(&tpm_dir).unwrap();
+
+            assert!(adopted);
+            assert!(installed);
+            assert!(tpm_dir.join("tmux-yank/.git").exists());
+            assert!(!adopted_missing);
+        });
+    }
+
+    #[test]
+    fn test_copy_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("tmux-yank");
+        std::fs::create_dir_all(source.join("scripts")).unwrap();
+        std::fs::write(source.join("yank.tmux"), "#!/bin/sh").unwrap();
+        std::fs::set_permissions(
+            source.join("yank.tmux"),
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink("../yank.tmux", source.join("scripts/link")).unwrap();
+
+        let target = temp.path().join("copy");
+        copy_dir(&source, &target).unwrap();
+
+        let mode = std::fs::metadata(target.join("yank.tmux"))
+            .unwrap()
+            .permissions()
+            .mode();
+        let link = std::fs::read_link(target.join("scripts/link")).unwrap();
+
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(link, Path::new("../yank.tmux"));
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            split_words(r#"set -g @a "x \"y\"" # comment"#),
+            vec!["set", "-g", "@a", "x \"y\""]
+        );
+        assert_eq!(
+            split_words("set -g @color '#[fg=red]'"),
+            vec!["set", "-g", "@color", "#[fg=red]"]
+        );
+    }
+}
@@ -1,12 +1,11 @@
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
 
 use miette::{IntoDiagnostic, Result};
 
-use crate::muxi::path;
+use crate::muxi::{GitSettings, path};
 
 use super::Plugin;
-use super::shared::display_path;
+use super::shared::{display_path, git_remote, with_retries};
 
 impl Plugin {
     pub fn is_installed(&self) -> bool {
@@ -14,7 +13,7 @@ impl Plugin {
     }
 
     /// Install this plugin to the plugins directory
-    pub fn install(&self) -> Result<bool> {
+    pub fn install(&self, git_settings: &GitSettings) -> Result<bool> {
         if let Some(path) = &self.path {
             if path.exists() {
                 return Ok(false);
@@ -40,19 +39,39 @@ impl Plugin {
         let target = install_path(self);
         let target_str = target.to_string_lossy();
 
-        let status = Command::new("git")
-            .args(["clone", url, &target_str])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .into_diagnostic()?;
+        let depth = self
+            .clone_depth(git_settings)
+            .map(|depth| format!("--depth={depth}"));
+        let filter = self
+            .clone_filter(git_settings)
+            .map(|filter| format!("--filter={filter}"));
+
+        let mut args = vec!["clone"];
+        args.extend(depth.as_deref());
+        args.extend(filter.as_deref());
+        args.extend([url, &target_str]);
+
+        with_retries(git_settings, || {
+            // Clean up whatever a failed attempt left behind
+            if target.exists() {
+                std::fs::remove_dir_all(&target).into_diagnostic()?;
+            }
 
-        if !status.success() {
-            return Err(miette::miette!("Failed to clone repository"));
-        }
+            git_remote(&args, &path::plugins_dir(), git_settings)
+        })?;
 
         Ok(true)
     }
+
+    /// `--depth` for this plugin, falling back to the global `git.depth`
+    pub(super) fn clone_depth(&self, git_settings: &GitSettings) -> Option<u32> {
+        self.depth.or(git_settings.depth)
+    }
+
+    /// `--filter` for this plugin, falling back to the global `git.filter`
+    pub(super) fn clone_filter<'a>(&'a self, git_settings: &'a GitSettings) -> Option<&'a str> {
+        self.filter.as_deref().or(git_settings.filter.as_deref())
+    }
 }
 
 pub(super) fn install_path(plugin: &Plugin) -> PathBuf {
@@ -95,6 +114,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clone_options_fall_back_to_git_settings() {
+        let mut plugin = Plugin::parse("tmux-plugins/tmux-continuum").unwrap();
+        let git_settings = GitSettings {
+            depth: Some(1),
+            filter: Some("blob:none".into()),
+            ..GitSettings::default()
+        };
+
+        assert_eq!(plugin.clone_depth(&git_settings), Some(1));
+        assert_eq!(plugin.clone_filter(&git_settings), Some("blob:none"));
+
+        plugin.depth = Some(10);
+        plugin.filter = Some("tree:0".into());
+
+        assert_eq!(plugin.clone_depth(&git_settings), Some(10));
+        assert_eq!(plugin.clone_filter(&git_settings), Some("tree:0"));
+        assert_eq!(plugin.clone_depth(&GitSettings::default()), Some(10));
+    }
+
     #[test]
     fn test_install_local_plugin_missing_path_errors() {
         let temp = std::env::temp_dir().join(format!("muxi-test-{}", uuid::Uuid::new_v4()));
@@ -104,7 +143,9 @@ mod tests {
             std::fs::remove_dir_all(&temp).unwrap();
         }
 
-        let error = plugin.install().expect_err("expected install to fail");
+        let error = plugin
+            .install(&GitSettings::default())
+            .expect_err("expected install to fail");
         assert!(format!("{error}").contains("does not exist"));
     }
 
@@ -114,7 +155,7 @@ mod tests {
         std::fs::create_dir_all(&temp).unwrap();
 
         let plugin = Plugin::new_local(temp.clone(), None);
-        let result = plugin.install().unwrap();
+        let result = plugin.install(&GitSettings::default()).unwrap();
 
         assert!(!result);
         std::fs::remove_dir_all(&temp).unwrap();
@@ -1,12 +1,20 @@
+mod history;
+mod import;
 mod install;
 mod model;
 mod options;
+mod rollback;
 mod shared;
 mod source;
 mod status;
 mod update;
 
+pub use history::*;
+pub use import::*;
 pub use model::*;
 pub use options::*;
+pub use rollback::*;
+pub use shared::GitTimeout;
+pub use source::unset_plugin_options;
 pub use status::*;
 pub use update::*;
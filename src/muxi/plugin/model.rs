@@ -20,6 +20,10 @@ pub struct Plugin {
     pub name: String,
     #[serde(default)]
     pub options: PluginOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
 }
 
 impl Plugin {
@@ -30,6 +34,8 @@ impl Plugin {
             path: None,
             name,
             options: PluginOptions::default(),
+            depth: None,
+            filter: None,
         }
     }
 
@@ -45,6 +51,8 @@ impl Plugin {
             path: Some(expanded),
             name,
             options: PluginOptions::default(),
+            depth: None,
+            filter: None,
         }
     }
 
@@ -70,6 +78,12 @@ impl Plugin {
         self.options = options;
         self
     }
+
+    fn with_clone_options(mut self, depth: Option<u32>, filter: Option<String>) -> Self {
+        self.depth = depth;
+        self.filter = filter;
+        self
+    }
 }
 
 impl<'de> Deserialize<'de> for Plugin {
@@ -100,6 +114,8 @@ impl<'de> Deserialize<'de> for Plugin {
                 let mut url: Option<String> = None;
                 let mut path_value: Option<PathBuf> = None;
                 let mut options: Option<PluginOptions> = None;
+                let mut depth: Option<u32> = None;
+                let mut filter: Option<String> = None;
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
@@ -121,6 +137,18 @@ impl<'de> Deserialize<'de> for Plugin {
                             }
                             options = Some(map.next_value()?);
                         }
+                        "depth" => {
+                            if depth.is_some() {
+                                return Err(serde::de::Error::duplicate_field("depth"));
+                            }
+                            depth = Some(map.next_value()?);
+                        }
+                        "filter" => {
+                            if filter.is_some() {
+                                return Err(serde::de::Error::duplicate_field("filter"));
+                            }
+                            filter = Some(map.next_value()?);
+                        }
                         _ => {
                             let _ = map.next_value::<IgnoredAny>()?;
                         }
@@ -140,13 +168,19 @@ impl<'de> Deserialize<'de> for Plugin {
                         None => None,
                     };
 
-                    return Ok(Plugin::new_local(path, url_value).with_options(options));
+                    return Ok(Plugin::new_local(path, url_value)
+                        .with_options(options)
+                        .with_clone_options(depth, filter));
                 }
 
                 if let Some(url_string) = url {
                     return Plugin::parse(&url_string)
                         .map_err(serde::de::Error::custom)
-                        .map(|plugin| plugin.with_options(options));
+                        .map(|plugin| {
+                            plugin
+                                .with_options(options)
+                                .with_clone_options(depth, filter)
+                        });
                 }
 
                 Err(serde::de::Error::custom(
@@ -182,6 +216,7 @@ fn extract_repo_name(url: &Url) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tmux::{OptionScope, OptionValue, TmuxOption};
     use mlua::{Lua, LuaSerdeExt, Value as LuaValue};
 
     #[test]
@@ -231,6 +266,8 @@ mod tests {
                     opts = {
                         copy_mode_put = "Space",
                         yank_selection_mouse = "clipboard",
+                        yank_with_mouse = true,
+                        yank_action = { value = "copy-pipe", scope = "server" },
                     },
                 }
                 "#,
@@ -242,8 +279,19 @@ mod tests {
         assert_eq!(plugin.name, "tmux-yank");
 
         let expected: PluginOptions = [
-            ("copy_mode_put".to_string(), "Space".to_string()),
-            ("yank_selection_mouse".to_string(), "clipboard".to_string()),
+            ("copy_mode_put".to_string(), "Space".into()),
+            ("yank_selection_mouse".to_string(), "clipboard".into()),
+            (
+                "yank_with_mouse".to_string(),
+                OptionValue::Bool(true).into(),
+            ),
+            (
+                "yank_action".to_string(),
+                TmuxOption {
+                    value: OptionValue::String("copy-pipe".into()),
+                    scope: OptionScope::Server,
+                },
+            ),
         ]
         .into_iter()
         .collect();
@@ -251,6 +299,19 @@ mod tests {
         assert_eq!(plugin.options, expected);
     }
 
+    #[test]
+    fn test_plugin_parse_with_clone_options() {
+        let lua = Lua::new();
+        let value = lua
+            .load(r#"return { url = "tmux-plugins/tmux-yank", depth = 1, filter = "blob:none" }"#)
+            .eval::<LuaValue>()
+            .unwrap();
+        let plugin: Plugin = lua.from_value(value).unwrap();
+
+        assert_eq!(plugin.depth, Some(1));
+        assert_eq!(plugin.filter.as_deref(), Some("blob:none"));
+    }
+
     #[test]
     fn test_plugin_local_path_only() {
         let plugin = Plugin::new_local(PathBuf::from("~/dev/my-plugin"), None);
@@ -1,12 +1,18 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Display;
 
+use miette::{IntoDiagnostic, Result};
 use owo_colors::OwoColorize;
 use serde::{Deserialize, Serialize};
 
+use crate::muxi::path;
+use crate::tmux::{OptionScope, TmuxOption};
+
+use super::Plugin;
+
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct PluginOptions(BTreeMap<String, String>);
+pub struct PluginOptions(BTreeMap<String, TmuxOption>);
 
 impl PluginOptions {
     pub fn is_empty(&self) -> bool {
@@ -19,7 +25,7 @@ impl PluginOptions {
 }
 
 impl std::ops::Deref for PluginOptions {
-    type Target = BTreeMap<String, String>;
+    type Target = BTreeMap<String, TmuxOption>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -33,16 +39,16 @@ impl std::ops::DerefMut for PluginOptions {
 }
 
 impl<'a> IntoIterator for &'a PluginOptions {
-    type Item = (&'a String, &'a String);
-    type IntoIter = std::collections::btree_map::Iter<'a, String, String>;
+    type Item = (&'a String, &'a TmuxOption);
+    type IntoIter = std::collections::btree_map::Iter<'a, String, TmuxOption>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.iter()
     }
 }
 
-impl FromIterator<(String, String)> for PluginOptions {
-    fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+impl FromIterator<(String, TmuxOption)> for PluginOptions {
+    fn from_iter<T: IntoIterator<Item = (String, TmuxOption)>>(iter: T) -> Self {
         Self(BTreeMap::from_iter(iter))
     }
 }
@@ -55,7 +61,7 @@ impl Display for PluginOptions {
 
         let total = self.len();
 
-        for (index, (key, value)) in self.0.iter().enumerate() {
+        for (index, (key, option)) in self.0.iter().enumerate() {
             if index > 0 {
                 writeln!(f)?;
             }
@@ -71,10 +77,178 @@ impl Display for PluginOptions {
                 "{} {} {}",
                 connector.dimmed(),
                 format!("@{key}").dimmed(),
-                value.green().bold()
+                option.value.green().bold()
             )?;
+
+            if option.scope != OptionScope::default() {
+                write!(f, " {}", format!("({})", option.scope).dimmed())?;
+            }
         }
 
         Ok(())
     }
 }
+
+/// A plugin option muxi set in tmux
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct AppliedOption {
+    pub key: String,
+    pub scope: OptionScope,
+}
+
+/// Plugin options set by the last `muxi plugins init`, by plugin name
+/// Used to unset the options that have been removed from the config since
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AppliedOptions(BTreeMap<String, BTreeSet<AppliedOption>>);
+
+impl AppliedOptions {
+    pub fn load() -> Result<Self> {
+        let file = path::applied_options_file();
+
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(file).into_diagnostic()?;
+
+        toml_edit::de::from_str(&contents).into_diagnostic()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let toml = toml_edit::ser::to_string(self).into_diagnostic()?;
+
+        std::fs::create_dir_all(path::muxi_data()).into_diagnostic()?;
+        std::fs::write(path::applied_options_file(), toml).into_diagnostic()
+    }
+
+    /// Records the options of `plugins` and returns the previously applied ones
+    /// that aren't configured anymore
+    ///
+    /// When `all_plugins` is set, `plugins` is the whole config, so the options
+    /// of plugins missing from it are returned too
+    pub fn track(&mut self, plugins: &[Plugin], all_plugins: bool) -> Vec<AppliedOption> {
+        let mut stale = Vec::new();
+
+        if all_plugins {
+            self.0.retain(|name, options| {
+                let configured = plugins.iter().any(|plugin| &plugin.name == name);
+
+                if !configured {
+                    stale.extend(options.iter().cloned());
+                }
+
+                configured
+            });
+        }
+
+        for plugin in plugins {
+            let current = plugin
+                .options
+                .iter()
+                .map(|(key, option)| AppliedOption {
+                    key: key.clone(),
+                    scope: option.scope,
+                })
+                .collect::<BTreeSet<_>>();
+
+            if let Some(previous) = self.0.get(&plugin.name) {
+                stale.extend(previous.difference(&current).cloned());
+            }
+
+            if current.is_empty() {
+                self.0.remove(&plugin.name);
+            } else {
+                self.0.insert(plugin.name.clone(), current);
+            }
+        }
+
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin_with_options(spec: &str, options: &[(&str, OptionScope)]) -> Plugin {
+        let mut plugin = Plugin::parse(spec).unwrap();
+
+        for (key, scope) in options {
+            let mut option = TmuxOption::from("value");
+            option.scope = *scope;
+            plugin.options.insert((*key).to_string(), option);
+        }
+
+        plugin
+    }
+
+    fn applied(key: &str, scope: OptionScope) -> AppliedOption {
+        AppliedOption {
+            key: key.into(),
+            scope,
+        }
+    }
+
+    #[test]
+    fn test_track_returns_removed_options() {
+        let mut applied_options = AppliedOptions::default();
+
+        let before = plugin_with_options(
+            "tmux-plugins/tmux-yank",
+            &[
+                ("yank_a", OptionScope::Global),
+                ("yank_b", OptionScope::Global),
+            ],
+        );
+        assert!(applied_options.track(&[before], true).is_empty());
+
+        let after = plugin_with_options(
+            "tmux-plugins/tmux-yank",
+            &[
+                ("yank_a", OptionScope::Global),
+                ("yank_b", OptionScope::Server),
+            ],
+        );
+        let stale = applied_options.track(&[after], true);
+
+        assert_eq!(stale, vec![applied("yank_b", OptionScope::Global)]);
+    }
+
+    #[test]
+    fn test_track_removed_plugins_only_with_all_plugins() {
+        let mut applied_options = AppliedOptions::default();
+
+        let yank =
+            plugin_with_options("tmux-plugins/tmux-yank", &[("yank_a", OptionScope::Global)]);
+        let cpu = plugin_with_options("tmux-plugins/tmux-cpu", &[("cpu_a", OptionScope::Window)]);
+        applied_options.track(&[yank.clone(), cpu], true);
+
+        assert!(
+            applied_options
+                .track(std::slice::from_ref(&yank), false)
+                .is_empty()
+        );
+        assert_eq!(
+            applied_options.track(&[yank], true),
+            vec![applied("cpu_a", OptionScope::Window)]
+        );
+    }
+
+    #[test]
+    fn test_applied_options_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path()), || {
+            let mut applied_options = AppliedOptions::default();
+            let plugin =
+                plugin_with_options("tmux-plugins/tmux-cpu", &[("cpu_a", OptionScope::Server)]);
+            applied_options.track(&[plugin], true);
+            applied_options.save().unwrap();
+
+            let loaded = AppliedOptions::load().unwrap();
+
+            assert_eq!(loaded, applied_options);
+        });
+    }
+}
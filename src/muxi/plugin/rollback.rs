@@ -0,0 +1,110 @@
+use miette::Result;
+
+use super::Plugin;
+use super::history::{read_history, write_history};
+use super::install::install_path;
+use super::shared::{display_path, ensure_exists, git};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginRollbackStatus {
+    RolledBack { from: String, to: String },
+    NoHistory,
+    Local { path: String },
+}
+
+impl Plugin {
+    /// Reset this plugin to the revision it was at before its last update
+    pub fn rollback(&self) -> Result<PluginRollbackStatus> {
+        if let Some(path) = &self.path {
+            ensure_exists(path)?;
+            return Ok(PluginRollbackStatus::Local {
+                path: display_path(path),
+            });
+        }
+
+        if !self.is_installed() {
+            return Err(miette::miette!("Plugin is not installed"));
+        }
+
+        let mut entries = read_history(self)?;
+        let Some(entry) = entries.pop() else {
+            return Ok(PluginRollbackStatus::NoHistory);
+        };
+
+        let dir = install_path(self);
+
+        let from = git(&["rev-parse", "--short", "HEAD"], &dir)?;
+        git(&["reset", "--hard", &entry.commit], &dir)?;
+        let to = git(&["rev-parse", "--short", "HEAD"], &dir)?;
+
+        write_history(self, &entries)?;
+
+        Ok(PluginRollbackStatus::RolledBack { from, to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::super::history::record_revision;
+    use super::*;
+
+    fn commit(dir: &Path, message: &str) -> String {
+        git(
+            &[
+                "-c",
+                "user.name=muxi",
+                "-c",
+                "user.email=muxi@example.com",
+                "commit",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+            dir,
+        )
+        .unwrap();
+
+        git(&["rev-parse", "HEAD"], dir).unwrap()
+    }
+
+    #[test]
+    fn test_rollback_local_plugin_returns_status() {
+        let temp = tempfile::tempdir().unwrap();
+
+        let plugin = Plugin::new_local(temp.path().to_path_buf(), None);
+        let status = plugin.rollback().unwrap();
+
+        assert!(matches!(status, PluginRollbackStatus::Local { .. }));
+    }
+
+    #[test]
+    fn test_rollback_resets_to_recorded_revision() {
+        let temp = tempfile::tempdir().unwrap();
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path()), || {
+            let plugin = Plugin::parse("tmux-plugins/tmux-continuum").unwrap();
+            let dir = install_path(&plugin);
+            std::fs::create_dir_all(&dir).unwrap();
+
+            git(&["init", "--quiet"], &dir).unwrap();
+            let first = commit(&dir, "first");
+            commit(&dir, "second");
+
+            assert_eq!(plugin.rollback().unwrap(), PluginRollbackStatus::NoHistory);
+
+            record_revision(&plugin, &first).unwrap();
+            let history = plugin.history().unwrap();
+            let status = plugin.rollback().unwrap();
+            let head = git(&["rev-parse", "HEAD"], &dir).unwrap();
+            let remaining = read_history(&plugin).unwrap();
+
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].summary, "first");
+            assert!(matches!(status, PluginRollbackStatus::RolledBack { .. }));
+            assert_eq!(head, first);
+            assert!(remaining.is_empty());
+        });
+    }
+}
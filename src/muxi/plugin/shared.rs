@@ -1,25 +1,197 @@
+use std::io::Read;
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use miette::{IntoDiagnostic, Result};
+use miette::{Diagnostic, IntoDiagnostic, Result};
+use thiserror::Error;
+
+use crate::muxi::GitSettings;
+
+/// Base delay between retries, doubled on every attempt
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Interval used to poll a running git process
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// git errors from the network or the remote, worth another attempt
+/// Anything else (a bad URL, a missing ref, a diverged branch) fails the same way every time
+const TRANSIENT_GIT_ERRORS: [&str; 10] = [
+    "could not resolve host",
+    "connection timed out",
+    "connection refused",
+    "connection reset",
+    "operation timed out",
+    "network is unreachable",
+    "temporary failure",
+    "the remote end hung up unexpectedly",
+    "early eof",
+    "the requested url returned error: 5",
+];
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("git {command} timed out after {}s", timeout.as_secs())]
+#[diagnostic(
+    code(muxi::plugin::git_timeout),
+    help("Check your network connection or raise `git.timeout` in your config")
+)]
+pub struct GitTimeout {
+    pub command: String,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("git {command} failed: {stderr}")]
+#[diagnostic(code(muxi::plugin::git_failed))]
+pub struct GitFailed {
+    pub command: String,
+    pub stderr: String,
+}
+
+impl GitFailed {
+    fn is_transient(&self) -> bool {
+        let stderr = self.stderr.to_lowercase();
+
+        TRANSIENT_GIT_ERRORS
+            .iter()
+            .any(|error| stderr.contains(error))
+    }
+}
 
 pub(super) fn git(args: &[&str], dir: &Path) -> Result<String> {
-    let output = Command::new("git")
+    let output = git_command(args, dir).output().into_diagnostic()?;
+
+    git_stdout(args, &output)
+}
+
+/// Runs a git command that talks to a remote, killing it after `settings.timeout`
+pub(super) fn git_remote(args: &[&str], dir: &Path, settings: &GitSettings) -> Result<String> {
+    let mut command = git_command(args, dir);
+
+    let Some(output) = output_with_timeout(&mut command, settings.timeout()).into_diagnostic()?
+    else {
+        return Err(GitTimeout {
+            command: args[0].to_string(),
+            timeout: settings.timeout().unwrap_or_default(),
+        })?;
+    };
+
+    git_stdout(args, &output)
+}
+
+/// Calls `operation` until it succeeds, at most `settings.retries + 1` times
+/// Only timeouts and network failures are retried
+pub(super) fn with_retries<T>(
+    settings: &GitSettings,
+    mut operation: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut attempt = 0;
+
+    loop {
+        match operation() {
+            Ok(value) => return Ok(value),
+            Err(error) if attempt >= settings.retries || !is_transient(&error) => {
+                return Err(error);
+            }
+            Err(_) => {
+                thread::sleep(RETRY_BACKOFF * 2u32.pow(attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+fn is_transient(error: &miette::Report) -> bool {
+    error.downcast_ref::<GitTimeout>().is_some()
+        || error
+            .downcast_ref::<GitFailed>()
+            .is_some_and(GitFailed::is_transient)
+}
+
+fn git_command(args: &[&str], dir: &Path) -> Command {
+    let mut command = Command::new("git");
+
+    command
         .args(args)
         .current_dir(dir)
+        // Fail instead of waiting for credentials nobody is going to type
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .into_diagnostic()?;
+        .stderr(Stdio::piped());
 
+    command
+}
+
+fn git_stdout(args: &[&str], output: &Output) -> Result<String> {
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(miette::miette!("git {} failed: {}", args[0], stderr.trim()));
+        return Err(GitFailed {
+            command: args[0].to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+        .into());
     }
 
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Like `Command::output`, but kills the process after `timeout`
+/// Returns `None` if the process timed out
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> std::io::Result<Option<Output>> {
+    let Some(timeout) = timeout else {
+        return command.output().map(Some);
+    };
+
+    let mut child = command.spawn()?;
+
+    // Drain the pipes so the process doesn't block on a full buffer
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+
+    let deadline = Instant::now() + timeout;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+
+            // Don't join the readers: grandchildren (e.g. git-remote-https)
+            // may still hold the pipes open
+            return Ok(None);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let join = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .map(|reader| reader.join().unwrap_or_default())
+            .unwrap_or_default()
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: join(stdout),
+        stderr: join(stderr),
+    }))
+}
+
+fn read_to_end(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
 pub(super) fn display_path(path: &Path) -> String {
     if let Some(home) = dirs::home_dir()
         && let Ok(stripped) = path.strip_prefix(&home)
@@ -40,3 +212,113 @@ pub(super) fn ensure_exists(path: &Path) -> Result<()> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn test_output_with_timeout_kills_slow_process() {
+        let mut command = Command::new("sleep");
+        command
+            .arg("5")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let started = Instant::now();
+        let output = output_with_timeout(&mut command, Some(Duration::from_millis(100))).unwrap();
+
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_output_with_timeout_captures_output() {
+        let mut command = Command::new("echo");
+        command
+            .arg("hello")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let output = output_with_timeout(&mut command, Some(Duration::from_secs(5)))
+            .unwrap()
+            .expect("expected command to finish");
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+    }
+
+    #[test]
+    fn test_with_retries_stops_after_limit() {
+        let settings = GitSettings {
+            retries: 1,
+            ..GitSettings::default()
+        };
+        let attempts = Cell::new(0);
+
+        let result: Result<()> = with_retries(&settings, || {
+            attempts.set(attempts.get() + 1);
+            Err(GitTimeout {
+                command: "fetch".into(),
+                timeout: Duration::from_secs(1),
+            })?
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[test]
+    fn test_with_retries_retries_network_failures() {
+        let settings = GitSettings {
+            retries: 1,
+            ..GitSettings::default()
+        };
+        let attempts = Cell::new(0);
+
+        let result = with_retries(&settings, || {
+            attempts.set(attempts.get() + 1);
+
+            if attempts.get() == 1 {
+                Err(GitFailed {
+                    command: "clone".into(),
+                    stderr: "fatal: unable to access 'https://github.com/a/b/': Could not resolve host: github.com".into(),
+                })?;
+            }
+
+            Ok(attempts.get())
+        });
+
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[test]
+    fn test_with_retries_skips_permanent_failures() {
+        let attempts = Cell::new(0);
+
+        let result: Result<()> = with_retries(&GitSettings::default(), || {
+            attempts.set(attempts.get() + 1);
+            Err(GitFailed {
+                command: "pull".into(),
+                stderr: "fatal: Not possible to fast-forward, aborting.".into(),
+            })?
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn test_with_retries_returns_first_success() {
+        let attempts = Cell::new(0);
+
+        let result = with_retries(&GitSettings::default(), || {
+            attempts.set(attempts.get() + 1);
+            Ok(attempts.get())
+        });
+
+        assert_eq!(result.unwrap(), 1);
+    }
+}
@@ -1,56 +1,71 @@
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
 use miette::{IntoDiagnostic, Result};
 
-use super::Plugin;
+use crate::tmux::{TmuxClient, TmuxCommand, dry_run, print_command};
+
 use super::install::install_path;
 use super::shared::ensure_exists;
+use super::{AppliedOption, Plugin};
 
 impl Plugin {
     /// Sources the plugin
-    pub fn source(&self) -> Result<()> {
-        let root = install_path(self);
-        ensure_exists(&root)?;
+    pub fn source(&self, client: &impl TmuxClient) -> Result<()> {
+        let scripts = self.scripts()?;
 
-        self.apply_options()?;
+        self.apply_options(client)?;
 
-        let entries = std::fs::read_dir(&root).into_diagnostic()?;
+        for path in scripts {
+            let mut command = Command::new(&path);
 
-        for entry in entries.flatten() {
-            let path = entry.path();
+            if dry_run() {
+                print_command(&command);
+                continue;
+            }
 
-            // Execute all "*.tmux" files in the plugin directory
-            if path.extension().is_some_and(|ext| ext == "tmux") {
-                let status = Command::new(&path)
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::null())
-                    .status()
-                    .into_diagnostic()?;
+            let status = command
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .into_diagnostic()?;
 
-                if !status.success() {
-                    return Err(miette::miette!(
-                        "Failed to execute {}: script exited with {}",
-                        path.display(),
-                        status
-                    ));
-                }
+            if !status.success() {
+                return Err(miette::miette!(
+                    "Failed to execute {}: script exited with {}",
+                    path.display(),
+                    status
+                ));
             }
         }
 
         Ok(())
     }
 
-    fn apply_options(&self) -> Result<()> {
-        for (key, value) in &self.options {
-            let status = Command::new("tmux")
-                .arg("set")
-                .arg("-g")
-                .arg(format!("@{key}"))
-                .arg(value)
-                .status()
+    /// All "*.tmux" files in the plugin directory, sorted
+    pub fn scripts(&self) -> Result<Vec<PathBuf>> {
+        let root = install_path(self);
+        ensure_exists(&root)?;
+
+        let mut scripts = std::fs::read_dir(&root)
+            .into_diagnostic()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "tmux"))
+            .collect::<Vec<_>>();
+
+        scripts.sort();
+
+        Ok(scripts)
+    }
+
+    fn apply_options(&self, client: &impl TmuxClient) -> Result<()> {
+        for (key, option) in &self.options {
+            let output = client
+                .output(TmuxCommand::new().args(option.set_args(&format!("@{key}"))))
                 .into_diagnostic()?;
 
-            if !status.success() {
+            if !output.status.success() {
                 return Err(miette::miette!(
                     "Failed to configure option @{key} for {}",
                     self.name
@@ -61,3 +76,18 @@ impl Plugin {
         Ok(())
     }
 }
+
+/// Unsets plugin options that muxi applied before but aren't configured anymore
+pub fn unset_plugin_options(client: &impl TmuxClient, options: &[AppliedOption]) -> Result<()> {
+    for AppliedOption { key, scope } in options {
+        let output = client
+            .output(TmuxCommand::new().args(scope.unset_args(&format!("@{key}"))))
+            .into_diagnostic()?;
+
+        if !output.status.success() {
+            return Err(miette::miette!("Failed to unset option @{key}"));
+        }
+    }
+
+    Ok(())
+}
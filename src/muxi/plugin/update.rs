@@ -3,9 +3,12 @@ use std::time::{Duration, SystemTime};
 
 use miette::Result;
 
+use crate::muxi::GitSettings;
+
 use super::Plugin;
+use super::history::record_revision;
 use super::install::install_path;
-use super::shared::{display_path, ensure_exists, git};
+use super::shared::{display_path, ensure_exists, git, git_remote, with_retries};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PluginChange {
@@ -34,7 +37,7 @@ pub enum PluginUpdateStatus {
 
 impl Plugin {
     /// Update this plugin to the latest commit on the default branch
-    pub fn update(&self) -> Result<PluginUpdateStatus> {
+    pub fn update(&self, git_settings: &GitSettings) -> Result<PluginUpdateStatus> {
         if let Some(path) = &self.path {
             ensure_exists(path)?;
             return Ok(PluginUpdateStatus::Local {
@@ -43,7 +46,7 @@ impl Plugin {
         }
 
         if !self.is_installed() {
-            self.install()?;
+            self.install(git_settings)?;
             let dir = install_path(self);
             let to = git(&["rev-parse", "--short", "HEAD"], &dir)?;
             return Ok(PluginUpdateStatus::Updated {
@@ -59,7 +62,13 @@ impl Plugin {
         let before_short = git(&["rev-parse", "--short", "HEAD"], &dir)?;
         let before_full = git(&["rev-parse", "HEAD"], &dir)?;
 
-        git(&["pull", "--ff-only"], &dir)?;
+        if let Some(depth) = self.clone_depth(git_settings) {
+            pull_shallow(&dir, &before_full, depth, git_settings)?;
+        } else {
+            with_retries(git_settings, || {
+                git_remote(&["pull", "--ff-only"], &dir, git_settings)
+            })?;
+        }
 
         let after_short = git(&["rev-parse", "--short", "HEAD"], &dir)?;
         let after_full = git(&["rev-parse", "HEAD"], &dir)?;
@@ -70,6 +79,8 @@ impl Plugin {
             });
         }
 
+        record_revision(self, &before_full)?;
+
         let changes = collect_changes(
             &dir,
             &before_full,
@@ -123,6 +134,43 @@ impl Plugin {
     }
 }
 
+/// Maximum number of `--deepen` rounds before giving up and unshallowing
+const MAX_DEEPEN_ROUNDS: usize = 10;
+
+/// Fast-forwards a shallow clone, fetching only `depth` new commits
+/// and deepening until `before` is reachable so the changelog is complete
+fn pull_shallow(dir: &Path, before: &str, depth: u32, git_settings: &GitSettings) -> Result<()> {
+    let depth_arg = format!("--depth={depth}");
+    with_retries(git_settings, || {
+        git_remote(&["fetch", &depth_arg], dir, git_settings)
+    })?;
+
+    let deepen_arg = format!("--deepen={depth}");
+    let mut rounds = 0;
+
+    while !is_ancestor(dir, before, "@{upstream}") {
+        if rounds == MAX_DEEPEN_ROUNDS {
+            with_retries(git_settings, || {
+                git_remote(&["fetch", "--unshallow"], dir, git_settings)
+            })?;
+            break;
+        }
+
+        with_retries(git_settings, || {
+            git_remote(&["fetch", &deepen_arg], dir, git_settings)
+        })?;
+        rounds += 1;
+    }
+
+    git(&["merge", "--ff-only", "@{upstream}"], dir)?;
+
+    Ok(())
+}
+
+fn is_ancestor(dir: &Path, ancestor: &str, descendant: &str) -> bool {
+    git(&["merge-base", "--is-ancestor", ancestor, descendant], dir).is_ok()
+}
+
 fn collect_changes(
     dir: &Path,
     from: &str,
@@ -172,6 +220,70 @@ fn collect_changes(
 mod tests {
     use super::*;
 
+    fn commit(dir: &Path, message: &str) {
+        git(
+            &[
+                "-c",
+                "user.name=muxi",
+                "-c",
+                "user.email=muxi@example.com",
+                "commit",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+            dir,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_update_shallow_plugin_collects_all_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        let remote = temp.path().join("remote");
+        std::fs::create_dir_all(&remote).unwrap();
+
+        git(&["init", "--quiet"], &remote).unwrap();
+        commit(&remote, "first");
+        commit(&remote, "second");
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path().join("data")), || {
+            let url = url::Url::from_directory_path(&remote).unwrap();
+            let mut plugin = Plugin::parse(url.as_str()).unwrap();
+            plugin.name = "remote".into();
+            plugin.depth = Some(1);
+
+            let git_settings = GitSettings::default();
+            plugin.install(&git_settings).unwrap();
+
+            let dir = install_path(&plugin);
+            let shallow = git(&["rev-parse", "--is-shallow-repository"], &dir).unwrap();
+
+            for message in ["third", "fourth", "fifth"] {
+                commit(&remote, message);
+            }
+
+            let status = plugin.update(&git_settings);
+            let remote_head = git(&["rev-parse", "HEAD"], &remote).unwrap();
+            let head = git(&["rev-parse", "HEAD"], &dir).unwrap();
+
+            assert_eq!(shallow, "true");
+            assert_eq!(head, remote_head);
+
+            match status.unwrap() {
+                PluginUpdateStatus::Updated { changes, .. } => {
+                    let summaries = changes
+                        .iter()
+                        .map(|change| change.summary.as_str())
+                        .collect::<Vec<_>>();
+
+                    assert_eq!(summaries, vec!["fifth", "fourth", "third"]);
+                }
+                other => panic!("unexpected status: {other:?}"),
+            }
+        });
+    }
+
     #[test]
     fn test_update_local_plugin_missing_path_errors() {
         let temp = std::env::temp_dir().join(format!("muxi-test-{}", uuid::Uuid::new_v4()));
@@ -181,7 +293,9 @@ mod tests {
             std::fs::remove_dir_all(&temp).unwrap();
         }
 
-        let error = plugin.update().expect_err("expected update to fail");
+        let error = plugin
+            .update(&GitSettings::default())
+            .expect_err("expected update to fail");
         assert!(format!("{error}").contains("does not exist"));
     }
 
@@ -191,7 +305,7 @@ mod tests {
         std::fs::create_dir_all(&temp).unwrap();
 
         let plugin = Plugin::new_local(temp.clone(), None);
-        let status = plugin.update().unwrap();
+        let status = plugin.update(&GitSettings::default()).unwrap();
 
         match status {
             PluginUpdateStatus::Local { path } => {
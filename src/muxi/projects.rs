@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tmux::TmuxSession;
+
+use super::{Session, path};
+
+/// Where `muxi projects` looks for projects
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ProjectsSettings {
+    /// Directories to search
+    pub roots: Vec<PathBuf>,
+    /// How many levels below each root to search
+    pub max_depth: usize,
+    /// Files or directories that make a directory a project
+    pub markers: Vec<String>,
+    /// Globs of directories to skip, matched against their name, or against
+    /// their path relative to the root if they contain a `/`
+    pub ignore: Vec<String>,
+}
+
+impl Default for ProjectsSettings {
+    fn default() -> Self {
+        Self {
+            roots: vec![],
+            max_depth: 2,
+            markers: vec![".git".into()],
+            ignore: vec![],
+        }
+    }
+}
+
+impl ProjectsSettings {
+    /// Project directories under the roots, sorted
+    /// Projects aren't searched for nested projects
+    pub fn discover(&self) -> Vec<PathBuf> {
+        let mut projects = Vec::new();
+
+        for root in &self.roots {
+            let root = path::expand_tilde(root.clone());
+            self.walk(&root, &root, 0, &mut projects);
+        }
+
+        projects.sort();
+        projects.dedup();
+        projects
+    }
+
+    fn walk(&self, root: &Path, dir: &Path, depth: usize, projects: &mut Vec<PathBuf>) {
+        if self.is_project(dir) {
+            projects.push(dir.to_path_buf());
+            return;
+        }
+
+        if depth >= self.max_depth {
+            return;
+        }
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            // Symlinks aren't followed
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                continue;
+            }
+
+            let path = entry.path();
+
+            if !self.is_ignored(root, &path) {
+                self.walk(root, &path, depth + 1, projects);
+            }
+        }
+    }
+
+    fn is_project(&self, dir: &Path) -> bool {
+        self.markers.iter().any(|marker| dir.join(marker).exists())
+    }
+
+    fn is_ignored(&self, root: &Path, dir: &Path) -> bool {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let relative_path = dir.strip_prefix(root).unwrap_or(dir).to_string_lossy();
+
+        self.ignore.iter().any(|pattern| {
+            if pattern.contains('/') {
+                glob_match(pattern, &relative_path)
+            } else {
+                glob_match(pattern, &name)
+            }
+        })
+    }
+}
+
+/// Session for a project, named after its directory
+pub fn project_session(path: &Path) -> Session {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default()
+        // tmux doesn't allow them in session names
+        .replace(['.', ':'], "_");
+
+    Session {
+        name,
+        path: path.to_path_buf(),
+        ..Default::default()
+    }
+}
+
+/// Renames the session while a running tmux session has its name but
+/// another path, so two `api` projects become `api` and `api-2`
+pub fn disambiguate_session(mut session: Session, tmux_sessions: &[TmuxSession]) -> Session {
+    let base_name = session.name.clone();
+    let mut suffix = 1;
+
+    while let Some(running) = tmux_sessions
+        .iter()
+        .find(|tmux_session| tmux_session.name == session.name)
+    {
+        if path::same_path(&running.path, &session.path) {
+            break;
+        }
+
+        suffix += 1;
+        session.name = format!("{base_name}-{suffix}");
+    }
+
+    session
+}
+
+/// Matches `*` (any characters) and `?` (a single character)
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and of the text it's matching up to
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((star, matched)) = backtrack else {
+                    return false;
+                };
+
+                // Let the `*` match one more character
+                backtrack = Some((star, matched + 1));
+                p = star + 1;
+                t = matched + 1;
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("node_modules", "node_modules"));
+        assert!(glob_match(".*", ".cache"));
+        assert!(glob_match("*-old", "muxi-old"));
+        assert!(glob_match("vendor/*", "vendor/tmux"));
+        assert!(glob_match("v?", "v2"));
+        assert!(glob_match("*a*b", "xaxab"));
+        assert!(!glob_match(".*", "muxi"));
+        assert!(!glob_match("v?", "v10"));
+        assert!(!glob_match("*-old", "muxi-old-2"));
+    }
+
+    #[test]
+    fn test_discover_projects() {
+        let root = tempfile::tempdir().unwrap();
+
+        for dir in [
+            "rust/muxi/.git",
+            "rust/muxi/crates/nested/.git",
+            "rust/tokio/Cargo.toml",
+            "go/too/deep/.git",
+            "archive/old/.git",
+            "notes",
+        ] {
+            std::fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+
+        let settings = ProjectsSettings {
+            roots: vec![root.path().to_path_buf()],
+            max_depth: 2,
+            markers: vec![".git".into(), "Cargo.toml".into()],
+            ignore: vec!["archive".into()],
+        };
+
+        let projects = settings.discover();
+
+        assert_eq!(
+            projects,
+            vec![
+                root.path().join("rust/muxi"),
+                root.path().join("rust/tokio")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disambiguate_session() {
+        let tmux_session = |name: &str, path: &str| TmuxSession {
+            name: name.into(),
+            path: path.into(),
+            attached: 0,
+            windows: 1,
+            last_activity: std::time::SystemTime::now(),
+        };
+        let tmux_sessions = [
+            tmux_session("api", "/work/api"),
+            tmux_session("api-2", "/oss/api"),
+        ];
+
+        let name = |path: &str| {
+            disambiguate_session(project_session(Path::new(path)), &tmux_sessions).name
+        };
+
+        assert_eq!(name("/work/api"), "api");
+        assert_eq!(name("/oss/api"), "api-2");
+        assert_eq!(name("/home/api"), "api-3");
+        assert_eq!(name("/code/muxi"), "muxi");
+    }
+
+    #[test]
+    fn test_project_session() {
+        let session = project_session(Path::new("/code/my.site"));
+
+        assert_eq!(session.name, "my_site");
+        assert_eq!(session.path, PathBuf::from("/code/my.site"));
+    }
+}
@@ -0,0 +1,135 @@
+use miette::{IntoDiagnostic, Result};
+
+use crate::tmux::Key;
+
+use super::{Session, Sessions, path};
+
+/// Keys of the sessions switched to with muxi, most recent first
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SessionHistory(Vec<Key>);
+
+impl SessionHistory {
+    pub fn load() -> Result<Self> {
+        let file = path::session_history_file();
+
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(file).into_diagnostic()?;
+
+        Ok(Self(
+            contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(Key::new)
+                .collect(),
+        ))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = path::session_history_file();
+        let contents = self
+            .0
+            .iter()
+            .map(|key| format!("{key}\n"))
+            .collect::<String>();
+
+        std::fs::create_dir_all(file.parent().unwrap()).into_diagnostic()?;
+        std::fs::write(file, contents).into_diagnostic()
+    }
+
+    /// Moves `key` to the front of the history
+    pub fn record(&mut self, key: &Key) {
+        self.0.retain(|recorded| recorded != key);
+        self.0.insert(0, key.clone());
+    }
+
+    /// Most recently used session that isn't the one named `current`
+    pub fn last<'a>(
+        &self,
+        sessions: &'a Sessions,
+        current: Option<&str>,
+    ) -> Option<(&'a Key, &'a Session)> {
+        self.0
+            .iter()
+            .filter_map(|key| sessions.0.get_key_value(key))
+            .find(|(_, session)| Some(session.name.as_str()) != current)
+    }
+
+    /// Sessions ordered by most recent use, followed by the unused ones in key order
+    pub fn sort<'a>(&self, sessions: &'a Sessions) -> Vec<(&'a Key, &'a Session)> {
+        let mut sorted = self
+            .0
+            .iter()
+            .filter_map(|key| sessions.0.get_key_value(key))
+            .collect::<Vec<_>>();
+
+        sorted.extend(sessions.0.iter().filter(|(key, _)| !self.0.contains(key)));
+
+        sorted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(sessions: &[(&Key, &Session)]) -> Vec<String> {
+        sessions.iter().map(|(key, _)| key.to_string()).collect()
+    }
+
+    #[test]
+    fn test_record_moves_key_to_front() {
+        let mut history = SessionHistory::default();
+
+        history.record(&"a".into());
+        history.record(&"b".into());
+        history.record(&"a".into());
+
+        assert_eq!(history, SessionHistory(vec!["a".into(), "b".into()]));
+    }
+
+    #[test]
+    fn test_last_skips_current_and_removed_sessions() {
+        let sessions = Sessions::from_names(&[("a", "alpha"), ("b", "beta")]);
+        let history = SessionHistory(vec!["a".into(), "x".into(), "b".into()]);
+
+        let (key, _) = history.last(&sessions, Some("alpha")).unwrap();
+        assert_eq!(key.as_ref(), "b");
+
+        let (key, _) = history.last(&sessions, Some("other")).unwrap();
+        assert_eq!(key.as_ref(), "a");
+
+        assert_eq!(SessionHistory::default().last(&sessions, None), None);
+    }
+
+    #[test]
+    fn test_sort_by_most_recent_use() {
+        let sessions = Sessions::from_names(&[
+            ("a", "alpha"),
+            ("b", "beta"),
+            ("c", "gamma"),
+            ("d", "delta"),
+        ]);
+        let history = SessionHistory(vec!["c".into(), "x".into(), "a".into()]);
+
+        assert_eq!(keys(&history.sort(&sessions)), vec!["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+
+        temp_env::with_var("MUXI_DATA_PATH", Some(temp.path()), || {
+            let mut history = SessionHistory::default();
+            history.record(&"a".into());
+            history.record(&"b".into());
+            history.save().unwrap();
+
+            let loaded = SessionHistory::load().unwrap();
+
+            assert_eq!(loaded, history);
+        });
+    }
+}
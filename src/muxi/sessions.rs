@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use miette::{IntoDiagnostic, Result};
 use owo_colors::OwoColorize;
@@ -9,15 +10,29 @@ use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::tmux::Key;
 
-use super::path;
+use super::{SessionHistory, SessionSort, SessionsFile, is_reserved_key, path};
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
 pub struct Session {
     pub name: String,
     #[serde(deserialize_with = "expand_tilde")]
     pub path: PathBuf,
+    /// Name of the template in `[templates]` applied to this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Name of the first window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_name: Option<String>,
+    /// Command of the first window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub on_create: Vec<OnCreateAction>,
+    /// Window, or `<window>.<pane>`, to select once `on_create` finishes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
 }
 
 impl Session {
@@ -43,12 +58,18 @@ impl Session {
     pub fn on_create_path(&self, path: Option<&Path>) -> PathBuf {
         path.map_or_else(|| self.path.clone(), |path| self.resolve_path(path))
     }
+
+    pub fn expanded_env(&self) -> BTreeMap<String, String> {
+        expand_env(&self.env)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum OnCreateAction {
     NewWindow(NewWindow),
+    SendKeys(SendKeys),
+    Run(RunCommand),
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
@@ -63,6 +84,47 @@ pub struct NewWindow {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Types `keys` into a pane without pressing Enter
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct SendKeys {
+    pub keys: String,
+    /// Window, or `<window>.<pane>`, defaults to the active pane
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+/// Runs a shell command in the session path, before the next action
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct RunCommand {
+    pub command: String,
+    /// Don't wait for the command to finish
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub background: bool,
+    /// Seconds to wait before killing the command, 0 to disable,
+    /// ignored in the background
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Also show failures with `tmux display-message`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub display_errors: bool,
+}
+
+impl RunCommand {
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+            .filter(|&timeout| timeout > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+impl NewWindow {
+    pub fn expanded_env(&self) -> BTreeMap<String, String> {
+        expand_env(&self.env)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -70,7 +132,8 @@ pub struct Sessions(pub BTreeMap<Key, Session>);
 
 impl Sessions {
     pub fn save(&self) -> Result<()> {
-        let toml = toml_edit::ser::to_string(&self.0).into_diagnostic()?;
+        let file = SessionsFile::read()?.with_sessions(self);
+        let toml = file.to_toml().into_diagnostic()?;
         let sessions_file = path::sessions_file();
 
         std::fs::write(sessions_file, toml).into_diagnostic()?;
@@ -78,9 +141,78 @@ impl Sessions {
         Ok(())
     }
 
+    /// How these sessions are written to sessions.toml
+    pub fn to_toml(&self) -> Result<String> {
+        SessionsFile::default()
+            .with_sessions(self)
+            .to_toml()
+            .into_diagnostic()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Sessions in the given order
+    pub fn sorted(&self, sort: SessionSort) -> Result<Vec<(&Key, &Session)>> {
+        match sort {
+            SessionSort::Key => Ok(self.0.iter().collect()),
+            SessionSort::Mru => Ok(SessionHistory::load()?.sort(self)),
+        }
+    }
+
+    /// Key of the bookmark after the one named `current`, wrapping around
+    /// The first bookmark if `current` isn't bookmarked
+    pub fn next_key(&self, current: Option<&str>) -> Option<&Key> {
+        let keys = self.0.keys().collect::<Vec<_>>();
+
+        match self.position(current) {
+            Some(index) => keys.get((index + 1) % keys.len()).copied(),
+            None => keys.first().copied(),
+        }
+    }
+
+    /// Key of the bookmark before the one named `current`, wrapping around
+    /// The last bookmark if `current` isn't bookmarked
+    pub fn prev_key(&self, current: Option<&str>) -> Option<&Key> {
+        let keys = self.0.keys().collect::<Vec<_>>();
+
+        match self.position(current) {
+            Some(index) => keys.get((index + keys.len() - 1) % keys.len()).copied(),
+            None => keys.last().copied(),
+        }
+    }
+
+    /// First of `a`-`z` and `0`-`9` that isn't bookmarked, reserved nor `taken`
+    pub fn free_key(&self, taken: impl Fn(&Key) -> bool) -> Option<Key> {
+        ('a'..='z')
+            .chain('0'..='9')
+            .map(|c| Key::new(c.to_string()))
+            .find(|key| !self.0.contains_key(key) && !is_reserved_key(key) && !taken(key))
+    }
+
+    fn position(&self, name: Option<&str>) -> Option<usize> {
+        self.0
+            .values()
+            .position(|session| Some(session.name.as_str()) == name)
+    }
+}
+
+#[cfg(test)]
+impl Sessions {
+    /// Builds bookmarks from `(key, name)` pairs, each at `/code/<name>`
+    pub(crate) fn from_names(names: &[(&str, &str)]) -> Self {
+        Self(BTreeMap::from_iter(names.iter().map(|(key, name)| {
+            (
+                Key::new(key),
+                Session {
+                    name: (*name).to_string(),
+                    path: format!("/code/{name}").into(),
+                    ..Default::default()
+                },
+            )
+        })))
+    }
 }
 
 impl fmt::Display for Sessions {
@@ -117,6 +249,13 @@ impl fmt::Display for Sessions {
     }
 }
 
+/// Values are expanded when used, so saving the sessions keeps `${VAR}` and `~`
+fn expand_env(env: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    env.iter()
+        .map(|(name, value)| (name.clone(), path::expand_vars(value)))
+        .collect()
+}
+
 fn expand_tilde<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
 where
     D: Deserializer<'de>,
@@ -138,6 +277,41 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_next_and_prev_key_wrap_around() {
+        let sessions = Sessions::from_names(&[("a", "alpha"), ("b", "beta"), ("c", "gamma")]);
+
+        assert_eq!(sessions.next_key(Some("alpha")), Some(&"b".into()));
+        assert_eq!(sessions.next_key(Some("gamma")), Some(&"a".into()));
+        assert_eq!(sessions.prev_key(Some("beta")), Some(&"a".into()));
+        assert_eq!(sessions.prev_key(Some("alpha")), Some(&"c".into()));
+    }
+
+    #[test]
+    fn test_next_and_prev_key_outside_bookmarks() {
+        let sessions = Sessions::from_names(&[("a", "alpha"), ("b", "beta")]);
+
+        assert_eq!(sessions.next_key(Some("other")), Some(&"a".into()));
+        assert_eq!(sessions.prev_key(None), Some(&"b".into()));
+        assert_eq!(Sessions::from_names(&[]).next_key(None), None);
+    }
+
+    #[test]
+    fn test_free_key() {
+        let sessions = Sessions::from_names(&[("a", "alpha"), ("c", "gamma")]);
+
+        assert_eq!(sessions.free_key(|_| false), Some("b".into()));
+        assert_eq!(
+            sessions.free_key(|key| key.as_ref() == "b"),
+            Some("d".into())
+        );
+        assert_eq!(
+            sessions.free_key(|key| key.as_ref() != "0"),
+            Some("0".into())
+        );
+        assert_eq!(sessions.free_key(|_| true), None);
+    }
+
     #[test]
     fn test_valid_session() {
         let toml_string = r#"
@@ -150,7 +324,7 @@ mod tests {
             Session {
                 name: "dotfiles".into(),
                 path: path::expand_tilde("~/.dotfiles".into()),
-                on_create: Vec::new(),
+                ..Default::default()
             },
         );
 
@@ -167,7 +341,7 @@ mod tests {
             Session {
                 name: "dotfiles".into(),
                 path: "/home/user/.dotfiles".into(),
-                on_create: Vec::new(),
+                ..Default::default()
             },
         );
         sessions_map.insert(
@@ -175,7 +349,7 @@ mod tests {
             Session {
                 name: "project".into(),
                 path: "/home/user/projects/myproject".into(),
-                on_create: Vec::new(),
+                ..Default::default()
             },
         );
 
@@ -222,7 +396,9 @@ mod tests {
                     path: Some(PathBuf::from("../qmk_firmware")),
                     name: Some("firmware".into()),
                     command: None,
+                    env: BTreeMap::new(),
                 })],
+                ..Default::default()
             },
         );
 
@@ -231,12 +407,34 @@ mod tests {
         assert_eq!(session, Sessions(expected));
     }
 
+    #[test]
+    fn test_session_env_is_expanded_when_used() {
+        let toml_string = r#"
+            a = { name = "api", path = "/code/api", env = { AWS_PROFILE = "${MUXI_TEST_AWS_PROFILE}", NODE_ENV = "development" } }
+        "#;
+
+        let sessions = Sessions(toml_edit::de::from_str(toml_string).unwrap());
+        let session = &sessions.0[&Key::new("a")];
+
+        assert_eq!(session.env["AWS_PROFILE"], "${MUXI_TEST_AWS_PROFILE}");
+
+        temp_env::with_var("MUXI_TEST_AWS_PROFILE", Some("work"), || {
+            assert_eq!(
+                session.expanded_env(),
+                BTreeMap::from([
+                    ("AWS_PROFILE".to_string(), "work".to_string()),
+                    ("NODE_ENV".to_string(), "development".to_string()),
+                ])
+            );
+        });
+    }
+
     #[test]
     fn test_resolve_relative_on_create_path() {
         let session = Session {
             name: "qmk".into(),
             path: PathBuf::from("/tmp/qmk_userspace"),
-            on_create: Vec::new(),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -250,7 +448,7 @@ mod tests {
         let session = Session {
             name: "qmk".into(),
             path: PathBuf::from("/tmp/qmk_userspace"),
-            on_create: Vec::new(),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -1,13 +1,14 @@
 use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::time::Duration;
 
 use miette::Result;
 use owo_colors::OwoColorize;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::tmux::{Key, Popup};
+use crate::tmux::{Key, OptionScope, OptionValue, Popup};
 
-use super::{Plugin, lua};
+use super::{Plugin, ProjectsSettings, lua};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Settings {
@@ -15,11 +16,18 @@ pub struct Settings {
     pub tmux_prefix: bool,
     pub uppercase_overrides: bool,
     pub use_current_pane_path: bool,
+    #[serde(default)]
+    pub session_sort: SessionSort,
     pub plugins: Vec<Plugin>,
+    pub git: GitSettings,
     pub editor: EditorSettings,
     pub fzf: FzfSettings,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_bindings")]
     pub bindings: Bindings,
+    #[serde(default)]
+    pub options: OptionsSettings,
+    #[serde(default)]
+    pub projects: ProjectsSettings,
 }
 
 impl Settings {
@@ -44,10 +52,14 @@ impl Default for Settings {
             tmux_prefix: true,
             uppercase_overrides: true,
             use_current_pane_path: false,
+            session_sort: SessionSort::default(),
             plugins: vec![],
+            git: GitSettings::default(),
             editor: EditorSettings::default(),
             fzf: FzfSettings::default(),
             bindings: BTreeMap::default(),
+            options: OptionsSettings::default(),
+            projects: ProjectsSettings::default(),
         }
     }
 }
@@ -80,6 +92,12 @@ impl Display for Settings {
             "use_current_pane_path".dimmed(),
             self.use_current_pane_path.bold().green()
         )?;
+        writeln!(
+            f,
+            "{} {}",
+            "session_sort".dimmed(),
+            self.session_sort.bold().green()
+        )?;
 
         // Plugins
         writeln!(f, "\n{}", "Plugins".bold().underline())?;
@@ -102,6 +120,28 @@ impl Display for Settings {
             }
         }
 
+        // Git
+        writeln!(f, "\n{}", "Git".bold().underline())?;
+        writeln!(f, "{} {}", "jobs".dimmed(), self.git.jobs.bold().green())?;
+        writeln!(
+            f,
+            "{} {}",
+            "timeout".dimmed(),
+            format!("{}s", self.git.timeout).bold().green()
+        )?;
+        writeln!(
+            f,
+            "{} {}",
+            "retries".dimmed(),
+            self.git.retries.bold().green()
+        )?;
+        if let Some(depth) = self.git.depth {
+            writeln!(f, "{} {}", "depth".dimmed(), depth.bold().green())?;
+        }
+        if let Some(filter) = &self.git.filter {
+            writeln!(f, "{} {}", "filter".dimmed(), filter.bold().green())?;
+        }
+
         // Editor
         writeln!(f, "\n{}", "Editor".bold().underline())?;
         writeln!(
@@ -145,6 +185,44 @@ impl Display for Settings {
             self.fzf.args.join(" ").bold().green()
         )?;
 
+        // Projects
+        if !self.projects.roots.is_empty() {
+            writeln!(f, "\n{}", "Projects".bold().underline())?;
+            writeln!(
+                f,
+                "{} {}",
+                "roots".dimmed(),
+                self.projects
+                    .roots
+                    .iter()
+                    .map(|root| root.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .bold()
+                    .green()
+            )?;
+            writeln!(
+                f,
+                "{} {}",
+                "max_depth".dimmed(),
+                self.projects.max_depth.bold().green()
+            )?;
+            writeln!(
+                f,
+                "{} {}",
+                "markers".dimmed(),
+                self.projects.markers.join(" ").bold().green()
+            )?;
+            if !self.projects.ignore.is_empty() {
+                writeln!(
+                    f,
+                    "{} {}",
+                    "ignore".dimmed(),
+                    self.projects.ignore.join(" ").bold().green()
+                )?;
+            }
+        }
+
         // Bindings
         if !self.bindings.is_empty() {
             writeln!(f, "\n{}", "Bindings".bold().underline())?;
@@ -156,33 +234,269 @@ impl Display for Settings {
                 .max()
                 .unwrap();
 
-            for (key, binding) in &self.bindings {
+            for (key, binding) in self
+                .bindings
+                .iter()
+                .flat_map(|(key, bindings)| bindings.iter().map(move |binding| (key, binding)))
+            {
+                let kind = binding.kind();
+                let command = if kind == BindingKind::Lua {
+                    "<function>"
+                } else {
+                    &binding.command
+                };
+
                 write!(
                     f,
                     "{:<max_width_key$} {}",
                     key.bold().green(),
-                    binding.command.dimmed()
+                    command.dimmed()
                 )?;
 
-                if binding.popup.is_some() {
-                    write!(f, "{}", " (popup)".cyan())?;
+                match kind {
+                    BindingKind::Shell => (),
+                    BindingKind::Tmux => write!(f, "{}", " (tmux)".cyan())?,
+                    BindingKind::Popup => write!(f, "{}", " (popup)".cyan())?,
+                    BindingKind::Lua => write!(f, "{}", " (lua)".cyan())?,
+                }
+
+                if binding.table != default_binding_table() {
+                    write!(f, " {}", format!("[{}]", binding.table).yellow())?;
                 }
 
                 writeln!(f)?;
             }
         }
 
+        // Options
+        if !self.options.is_empty() {
+            writeln!(f, "\n{}", "Options".bold().underline())?;
+
+            let max_width_scope = self
+                .options
+                .iter()
+                .map(|(scope, _, _)| scope.to_string().len())
+                .max()
+                .unwrap();
+
+            for (scope, name, value) in self.options.iter() {
+                writeln!(
+                    f,
+                    "{:<max_width_scope$} {} {}",
+                    scope.to_string().dimmed(),
+                    name,
+                    value.bold().green()
+                )?;
+            }
+        }
+
         Ok(())
     }
 }
 
-pub type Bindings = BTreeMap<Key, Binding>;
+/// Order of the sessions in `sessions list`, the FZF popup and the tmux menu
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionSort {
+    /// By bookmark key
+    #[default]
+    Key,
+    /// Most recently used first
+    Mru,
+}
+
+impl Display for SessionSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key => f.write_str("key"),
+            Self::Mru => f.write_str("mru"),
+        }
+    }
+}
+
+/// A key can be bound in several tables, so each key holds a list of bindings
+pub type Bindings = BTreeMap<Key, Vec<Binding>>;
+
+/// Accepts a single binding table or a list of them for each key
+fn deserialize_bindings<'de, D>(deserializer: D) -> Result<Bindings, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct BindingsVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for BindingsVisitor {
+        type Value = Vec<Binding>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a binding table or a list of them")
+        }
+
+        fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+        where
+            M: serde::de::MapAccess<'de>,
+        {
+            Binding::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+                .map(|binding| vec![binding])
+        }
+
+        fn visit_seq<S>(self, seq: S) -> Result<Self::Value, S::Error>
+        where
+            S: serde::de::SeqAccess<'de>,
+        {
+            Vec::deserialize(serde::de::value::SeqAccessDeserializer::new(seq))
+        }
+    }
+
+    struct KeyBindings(Vec<Binding>);
+
+    impl<'de> Deserialize<'de> for KeyBindings {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer
+                .deserialize_any(BindingsVisitor)
+                .map(KeyBindings)
+        }
+    }
+
+    let bindings = BTreeMap::<Key, KeyBindings>::deserialize(deserializer)?;
+
+    Ok(bindings
+        .into_iter()
+        .map(|(key, KeyBindings(bindings))| (key, bindings))
+        .collect())
+}
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Binding {
+    /// Lua bindings hold a function instead, which is taken out before deserializing
+    #[serde(default)]
     pub command: String,
+    /// How `command` is run (default: `popup` if `popup` is set, `shell` otherwise)
+    #[serde(default)]
+    pub kind: Option<BindingKind>,
     #[serde(default)]
     pub popup: Option<Popup>,
+    /// Key table to bind into (`muxi`, `root`, `prefix`, `copy-mode-vi`...)
+    #[serde(default = "default_binding_table")]
+    pub table: String,
+    /// Allow repeating the key without pressing the prefix again (`bind -r`)
+    /// Not called `repeat` because that's a reserved word in Lua
+    #[serde(default, alias = "repeat")]
+    pub repeatable: bool,
+    /// Description shown by `tmux list-keys -N` (`bind -N`)
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl Default for Binding {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            kind: None,
+            popup: None,
+            table: default_binding_table(),
+            repeatable: false,
+            note: None,
+        }
+    }
+}
+
+impl Binding {
+    pub fn kind(&self) -> BindingKind {
+        self.kind.unwrap_or(if self.popup.is_some() {
+            BindingKind::Popup
+        } else {
+            BindingKind::Shell
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingKind {
+    /// Raw tmux command: `bind <key> <command>`
+    Tmux,
+    /// Shell command: `bind <key> run <command>`
+    Shell,
+    /// Shell command in a popup: `bind <key> popup -E <command>`
+    Popup,
+    /// Function defined in `init.lua`: `bind <key> run 'muxi run-binding <key>'`
+    Lua,
+}
+
+fn default_binding_table() -> String {
+    "muxi".to_string()
+}
+
+/// Plain tmux options, by scope
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+pub struct OptionsSettings {
+    #[serde(default)]
+    pub global: BTreeMap<String, OptionValue>,
+    #[serde(default)]
+    pub server: BTreeMap<String, OptionValue>,
+    #[serde(default)]
+    pub window: BTreeMap<String, OptionValue>,
+    #[serde(default)]
+    pub session: BTreeMap<String, OptionValue>,
+}
+
+impl OptionsSettings {
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty()
+            && self.server.is_empty()
+            && self.window.is_empty()
+            && self.session.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (OptionScope, &String, &OptionValue)> {
+        [
+            (OptionScope::Server, &self.server),
+            (OptionScope::Global, &self.global),
+            (OptionScope::Window, &self.window),
+            (OptionScope::Session, &self.session),
+        ]
+        .into_iter()
+        .flat_map(|(scope, options)| {
+            options
+                .iter()
+                .map(move |(name, value)| (scope, name, value))
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct GitSettings {
+    /// Maximum number of plugins processed at the same time
+    pub jobs: usize,
+    /// Seconds before a git operation is killed (0 disables it)
+    pub timeout: u64,
+    /// Times a git operation that timed out or hit a network error is retried
+    pub retries: u32,
+    /// Default `--depth` for plugin clones
+    pub depth: Option<u32>,
+    /// Default `--filter` for plugin clones (e.g. `blob:none`)
+    pub filter: Option<String>,
+}
+
+impl GitSettings {
+    pub fn timeout(&self) -> Option<Duration> {
+        (self.timeout > 0).then(|| Duration::from_secs(self.timeout))
+    }
+}
+
+impl Default for GitSettings {
+    fn default() -> Self {
+        Self {
+            jobs: 8,
+            timeout: 60,
+            retries: 2,
+            depth: None,
+            filter: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
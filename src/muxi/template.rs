@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{OnCreateAction, Session};
+
+/// Settings shared by the sessions with `template = "<name>"`
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
+pub struct Template {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_create: Vec<OnCreateAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Template {
+    /// Fills in what the session doesn't set
+    /// The template's `on_create` runs first, and the session's `env` wins
+    pub fn apply(&self, session: &Session) -> Session {
+        let mut on_create = self.on_create.clone();
+        on_create.extend(session.on_create.iter().cloned());
+
+        let mut env = self.env.clone();
+        env.extend(session.env.clone());
+
+        Session {
+            window_name: session
+                .window_name
+                .clone()
+                .or_else(|| self.window_name.clone()),
+            command: session.command.clone().or_else(|| self.command.clone()),
+            select: session.select.clone().or_else(|| self.select.clone()),
+            on_create,
+            env,
+            ..session.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::muxi::{NewWindow, SendKeys};
+
+    use super::*;
+
+    #[test]
+    fn test_apply_template() {
+        let template = Template {
+            window_name: Some("editor".into()),
+            command: Some("nvim".into()),
+            on_create: vec![OnCreateAction::NewWindow(NewWindow {
+                name: Some("shell".into()),
+                ..Default::default()
+            })],
+            select: Some("editor".into()),
+            env: BTreeMap::from([
+                ("RUST_BACKTRACE".into(), "1".into()),
+                ("CARGO_TARGET_DIR".into(), "target".into()),
+            ]),
+        };
+
+        let session = Session {
+            name: "muxi".into(),
+            path: "/code/muxi".into(),
+            template: Some("rust".into()),
+            command: Some("hx".into()),
+            on_create: vec![OnCreateAction::SendKeys(SendKeys {
+                keys: "cargo test".into(),
+                target: Some("shell".into()),
+            })],
+            env: BTreeMap::from([("CARGO_TARGET_DIR".into(), "/tmp/muxi".into())]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            template.apply(&session),
+            Session {
+                name: "muxi".into(),
+                path: "/code/muxi".into(),
+                template: Some("rust".into()),
+                window_name: Some("editor".into()),
+                command: Some("hx".into()),
+                on_create: vec![
+                    OnCreateAction::NewWindow(NewWindow {
+                        name: Some("shell".into()),
+                        ..Default::default()
+                    }),
+                    OnCreateAction::SendKeys(SendKeys {
+                        keys: "cargo test".into(),
+                        target: Some("shell".into()),
+                    }),
+                ],
+                select: Some("editor".into()),
+                env: BTreeMap::from([
+                    ("RUST_BACKTRACE".into(), "1".into()),
+                    ("CARGO_TARGET_DIR".into(), "/tmp/muxi".into()),
+                ]),
+            }
+        );
+    }
+}
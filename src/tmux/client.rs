@@ -0,0 +1,200 @@
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+use super::dry_run::is_query;
+use super::{Socket, dry_run, print_command, socket};
+
+/// Arguments of a tmux invocation, built like a `std::process::Command`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TmuxCommand {
+    args: Vec<OsString>,
+}
+
+impl TmuxCommand {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+
+        self
+    }
+
+    pub fn get_args(&self) -> &[OsString] {
+        &self.args
+    }
+}
+
+/// Runs tmux commands
+pub trait TmuxClient: Sync {
+    /// Runs the command and collects its output
+    fn output(&self, command: &TmuxCommand) -> io::Result<Output>;
+
+    /// Starts the command without waiting for it, with piped stdin and stdout
+    fn spawn(&self, command: &TmuxCommand) -> io::Result<()>;
+
+    /// Replaces the current process with the command
+    /// Only returns if tmux couldn't be executed, or with `Ok` if it wasn't run
+    fn exec(&self, command: &TmuxCommand) -> io::Result<()>;
+}
+
+/// Runs the `tmux` binary, on the socket selected with `--socket-name`/`--socket-path`
+/// With `--dry-run`, only queries run and every other command is printed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClient;
+
+impl SystemClient {
+    fn command(command: &TmuxCommand) -> Command {
+        let mut tmux = Command::new("tmux");
+
+        match socket() {
+            Some(Socket::Name(name)) => tmux.arg("-L").arg(name),
+            Some(Socket::Path(path)) => tmux.arg("-S").arg(path),
+            None => &mut tmux,
+        };
+
+        tmux.args(command.get_args());
+        tmux
+    }
+
+    /// Prints the command in dry-run mode, `true` if it was printed instead of run
+    fn print(command: &TmuxCommand) -> bool {
+        if !dry_run() || is_query(command.get_args()) {
+            return false;
+        }
+
+        print_command(&Self::command(command));
+
+        true
+    }
+}
+
+impl TmuxClient for SystemClient {
+    fn output(&self, command: &TmuxCommand) -> io::Result<Output> {
+        if Self::print(command) {
+            return Ok(Output {
+                status: ExitStatus::from_raw(0),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            });
+        }
+
+        Self::command(command).output()
+    }
+
+    fn spawn(&self, command: &TmuxCommand) -> io::Result<()> {
+        if Self::print(command) {
+            return Ok(());
+        }
+
+        Self::command(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map(|_| ())
+    }
+
+    fn exec(&self, command: &TmuxCommand) -> io::Result<()> {
+        if Self::print(command) {
+            return Ok(());
+        }
+
+        Err(Self::command(command).exec())
+    }
+}
+
+/// Records the commands instead of running them
+/// Every command succeeds with empty output unless told otherwise
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct RecordingClient {
+    calls: std::sync::Mutex<Vec<Vec<String>>>,
+    failing: Vec<&'static str>,
+    stdout: Vec<(&'static str, String)>,
+}
+
+#[cfg(test)]
+impl RecordingClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the commands starting with `subcommand` fail
+    pub fn failing(mut self, subcommand: &'static str) -> Self {
+        self.failing.push(subcommand);
+        self
+    }
+
+    /// Makes the commands starting with `subcommand` print `stdout`
+    pub fn with_stdout(mut self, subcommand: &'static str, stdout: &str) -> Self {
+        self.stdout.push((subcommand, stdout.to_string()));
+        self
+    }
+
+    /// Arguments of every command run so far
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, command: &TmuxCommand) -> Vec<String> {
+        let args = command
+            .get_args()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+
+        self.calls.lock().unwrap().push(args.clone());
+        args
+    }
+}
+
+#[cfg(test)]
+impl TmuxClient for RecordingClient {
+    fn output(&self, command: &TmuxCommand) -> io::Result<Output> {
+        let args = self.record(command);
+        let subcommand = args.first().map(String::as_str).unwrap_or_default();
+
+        let failed = self.failing.contains(&subcommand);
+        let stdout = self
+            .stdout
+            .iter()
+            .find(|(name, _)| *name == subcommand)
+            .map(|(_, stdout)| stdout.clone().into_bytes())
+            .unwrap_or_default();
+
+        Ok(Output {
+            // Raw wait statuses: exit code 1 is 256
+            status: ExitStatus::from_raw(if failed { 256 } else { 0 }),
+            stdout,
+            stderr: if failed {
+                format!("{subcommand} failed").into_bytes()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+
+    fn spawn(&self, command: &TmuxCommand) -> io::Result<()> {
+        self.record(command);
+        Ok(())
+    }
+
+    fn exec(&self, command: &TmuxCommand) -> io::Result<()> {
+        self.record(command);
+        Err(io::Error::other("exec is not supported by RecordingClient"))
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::path::Path;
+
+use super::TmuxCommand;
+use super::shell::shell_quote;
+
+/// A tmux.conf file, to be loaded with `tmux source-file`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TmuxConf {
+    lines: Vec<String>,
+}
+
+impl TmuxConf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn comment(&mut self, comment: &str) -> &mut Self {
+        for line in comment.lines() {
+            self.lines.push(format!("# {line}").trim_end().to_string());
+        }
+
+        self
+    }
+
+    pub fn blank_line(&mut self) -> &mut Self {
+        self.lines.push(String::new());
+        self
+    }
+
+    /// Adds a tmux command, one line per command of a `;` chain
+    pub fn command(&mut self, tmux_command: &TmuxCommand) -> &mut Self {
+        let args = tmux_command
+            .get_args()
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>();
+
+        for command in args.split(|arg| arg == ";") {
+            if command.is_empty() {
+                continue;
+            }
+
+            let line = command
+                .iter()
+                .map(|arg| conf_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            self.lines.push(line);
+        }
+
+        self
+    }
+
+    /// Adds `run-shell <script>`
+    pub fn run_shell(&mut self, script: &Path) -> &mut Self {
+        self.command(
+            TmuxCommand::new()
+                .arg("run-shell")
+                .arg(shell_quote(&script.to_string_lossy())),
+        )
+    }
+}
+
+impl fmt::Display for TmuxConf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes an argument for the tmux configuration parser
+/// Single quotes are taken literally, double quotes expand `$` and `\`
+pub(super) fn conf_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.to_string()
+    } else if !arg.contains('\'') {
+        format!("'{arg}'")
+    } else {
+        let escaped = arg
+            .replace('\\', r"\\")
+            .replace('"', r#"\""#)
+            .replace('$', r"\$");
+
+        format!("\"{escaped}\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conf_quote() {
+        assert_eq!(conf_quote("switch-client"), "switch-client");
+        assert_eq!(conf_quote(""), "''");
+        assert_eq!(conf_quote("#{session_path}"), "'#{session_path}'");
+        assert_eq!(
+            conf_quote("muxi sessions set a && tmux display 'bound $x'"),
+            r#""muxi sessions set a && tmux display 'bound \$x'""#
+        );
+    }
+
+    #[test]
+    fn command_splits_chains_into_lines() {
+        let mut conf = TmuxConf::new();
+        conf.comment("Generated by muxi")
+            .command(
+                TmuxCommand::new()
+                    .args(["set", "-g", "status-left", "#S "])
+                    .arg(";")
+                    .args(["bind", "-T", "muxi", "d", "run", "-b"])
+                    .arg("muxi sessions switch d")
+                    .arg(";"),
+            )
+            .blank_line()
+            .run_shell(Path::new("/plugins/tmux sensible/sensible.tmux"));
+
+        assert_eq!(
+            conf.to_string(),
+            r#"# Generated by muxi
+set -g status-left '#S '
+bind -T muxi d run -b 'muxi sessions switch d'
+
+run-shell "'/plugins/tmux sensible/sensible.tmux'"
+"#
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::ffi::OsString;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::shell::shell_quote;
+
+/// Set for this process with `--dry-run`
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// tmux commands that only read state, still run in dry-run mode
+const QUERIES: [&str; 6] = [
+    "capture-pane",
+    "has-session",
+    "list-clients",
+    "list-sessions",
+    "list-windows",
+    "show-options",
+];
+
+/// Makes every tmux and shell command of this process print instead of running
+pub fn set_dry_run() {
+    DRY_RUN.store(true, Ordering::Relaxed);
+}
+
+/// Whether commands are printed instead of run
+pub fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Whether the tmux command only reads state, so it's safe to run in dry-run mode
+pub(super) fn is_query(args: &[OsString]) -> bool {
+    let Some(subcommand) = args.first().and_then(|subcommand| subcommand.to_str()) else {
+        return false;
+    };
+
+    match subcommand {
+        // Without `-p` the message is shown in the client instead of printed
+        "display-message" => args[1..].iter().any(|arg| arg == "-p"),
+        subcommand => QUERIES.contains(&subcommand),
+    }
+}
+
+/// Prints a command in copy-pasteable form
+pub fn print_command(command: &Command) {
+    println!("{}", format_command(command));
+}
+
+/// Includes the working directory and environment set on the command, so it runs the same
+/// Ex: `cd /code/muxi && env RUST_LOG=debug sh -c 'cargo build'`
+fn format_command(command: &Command) -> String {
+    let mut words = Vec::new();
+
+    if let Some(dir) = command.get_current_dir() {
+        words.push(format!("cd {} &&", quote(&dir.to_string_lossy())));
+    }
+
+    if command.get_envs().len() > 0 {
+        words.push("env".to_string());
+
+        // `env` takes its options before the variables it sets
+        for (key, _) in command.get_envs().filter(|(_, value)| value.is_none()) {
+            words.push(format!("-u {}", quote(&key.to_string_lossy())));
+        }
+
+        for (key, value) in command.get_envs() {
+            if let Some(value) = value {
+                words.push(quote(&format!(
+                    "{}={}",
+                    key.to_string_lossy(),
+                    value.to_string_lossy()
+                )));
+            }
+        }
+    }
+
+    words.extend(
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| quote(&arg.to_string_lossy())),
+    );
+
+    words.join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    if arg == ";" {
+        // tmux command separator
+        r"\;".to_string()
+    } else {
+        shell_quote(arg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_command() {
+        let command = format_command(Command::new("tmux").args([
+            "bind",
+            "-T",
+            "muxi",
+            "d",
+            "run-shell",
+            "muxi sessions switch d",
+            ";",
+            "bind",
+            "-T",
+            "muxi",
+            "m",
+            "run-shell",
+            "muxi sessions switch m",
+        ]));
+
+        assert_eq!(
+            command,
+            r"tmux bind -T muxi d run-shell 'muxi sessions switch d' \; bind -T muxi m run-shell 'muxi sessions switch m'"
+        );
+    }
+
+    #[test]
+    fn test_format_command_with_dir_and_env() {
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "docker compose up -d"])
+            .current_dir("/code/my app")
+            .env("COMPOSE_PROFILES", "dev")
+            .env_remove("DEBUG");
+
+        assert_eq!(
+            format_command(&command),
+            "cd '/code/my app' && env -u DEBUG COMPOSE_PROFILES=dev sh -c 'docker compose up -d'"
+        );
+    }
+
+    #[test]
+    fn test_is_query() {
+        assert!(is_query(&[
+            "has-session".into(),
+            "-t".into(),
+            "muxi:".into()
+        ]));
+        assert!(!is_query(&["new-session".into(), "-d".into()]));
+        assert!(!is_query(&[]));
+    }
+
+    #[test]
+    fn test_is_query_display_message() {
+        assert!(is_query(&[
+            "display-message".into(),
+            "-p".into(),
+            "#S".into()
+        ]));
+        assert!(!is_query(&[
+            "display-message".into(),
+            "muxi: session failed".into()
+        ]));
+    }
+}
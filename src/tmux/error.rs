@@ -34,10 +34,40 @@ pub enum Error {
     #[diagnostic(code(muxi::tmux::create_failed))]
     Create(String, String),
 
+    #[error("failed to set the environment of session {0}: `{1}`")]
+    #[diagnostic(code(muxi::tmux::set_environment_failed))]
+    Environment(String, String),
+
     #[error("failed to create tmux window for session {0}: `{1}`")]
     #[diagnostic(code(muxi::tmux::new_window_failed))]
     NewWindow(String, String),
 
+    #[error("failed to type keys in session {0}: `{1}`")]
+    #[diagnostic(code(muxi::tmux::send_keys_failed))]
+    SendKeys(String, String),
+
+    #[error("failed to select {1} in session {0}: `{2}`")]
+    #[diagnostic(
+        code(muxi::tmux::select_failed),
+        help("`select` takes a window name or index, optionally followed by `.<pane>`")
+    )]
+    Select(String, String, String),
+
+    #[error("`{1}` failed in session {0}: `{2}`")]
+    #[diagnostic(code(muxi::tmux::run_failed))]
+    Run(String, String, String),
+
+    #[error("`{1}` timed out after {2}s in session {0}")]
+    #[diagnostic(
+        code(muxi::tmux::run_timeout),
+        help("Raise its `timeout`, or set `background = true` to not wait for it")
+    )]
+    RunTimeout(String, String, u64),
+
+    #[error("failed to capture pane of session {0}: `{1}`")]
+    #[diagnostic(code(muxi::tmux::capture_pane_failed))]
+    CapturePane(String, String),
+
     #[error("failed to generate tmux menu: `{0}`")]
     #[diagnostic(code(muxi::tmux::menu_failed))]
     DisplayMenu(String),
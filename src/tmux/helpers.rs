@@ -1,18 +1,30 @@
-use std::path::PathBuf;
-use std::process::Command;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::muxi::{NewWindow, OnCreateAction, Session, Sessions};
+use rustix::process::{Pid, Signal, kill_process_group};
 
-use super::{Error, TmuxResult};
+use crate::muxi::{NewWindow, OnCreateAction, RunCommand, SendKeys, Session};
+
+use super::conf::conf_quote;
+use super::session::LIST_SESSIONS_FORMAT;
+use super::shell::shell_quote;
+use super::{Error, Key, TmuxClient, TmuxCommand, TmuxResult, TmuxSession, dry_run, print_command};
 
 /// Captures de current session's name
 /// Equivalent to: `tmux display-message -p '#S'`
-pub fn current_session_name() -> Option<String> {
-    let output = Command::new("tmux")
-        .arg("display-message")
-        .arg("-p")
-        .arg("#S")
-        .output()
+pub fn current_session_name(client: &impl TmuxClient) -> Option<String> {
+    let output = client
+        .output(
+            TmuxCommand::new()
+                .arg("display-message")
+                .arg("-p")
+                .arg("#S"),
+        )
         .ok()?;
 
     if output.status.success() {
@@ -24,12 +36,14 @@ pub fn current_session_name() -> Option<String> {
 
 /// Captures de current session's path
 /// Equivalent to: `tmux display-message -p '#{session_path}'`
-pub fn current_session_path() -> Option<PathBuf> {
-    let output = Command::new("tmux")
-        .arg("display-message")
-        .arg("-p")
-        .arg("#{session_path}")
-        .output()
+pub fn current_session_path(client: &impl TmuxClient) -> Option<PathBuf> {
+    let output = client
+        .output(
+            TmuxCommand::new()
+                .arg("display-message")
+                .arg("-p")
+                .arg("#{session_path}"),
+        )
         .ok()?;
 
     if output.status.success() {
@@ -41,12 +55,14 @@ pub fn current_session_path() -> Option<PathBuf> {
 
 /// Captures de current pane's path
 /// Equivalent to: `tmux display-message -p '#{pane_current_path}'`
-pub fn current_pane_path() -> Option<PathBuf> {
-    let output = Command::new("tmux")
-        .arg("display-message")
-        .arg("-p")
-        .arg("#{pane_current_path}")
-        .output()
+pub fn current_pane_path(client: &impl TmuxClient) -> Option<PathBuf> {
+    let output = client
+        .output(
+            TmuxCommand::new()
+                .arg("display-message")
+                .arg("-p")
+                .arg("#{pane_current_path}"),
+        )
         .ok()?;
 
     if output.status.success() {
@@ -58,12 +74,13 @@ pub fn current_pane_path() -> Option<PathBuf> {
 
 /// Check if a tmux session exists
 /// Equivalent to: `tmux has-session -t <session_name>:`
-pub fn has_session(session: &Session) -> bool {
-    let output = Command::new("tmux")
-        .arg("has-session")
-        .arg("-t")
-        .arg(session_target(&session.name))
-        .output();
+pub fn has_session(client: &impl TmuxClient, session: &Session) -> bool {
+    let output = client.output(
+        TmuxCommand::new()
+            .arg("has-session")
+            .arg("-t")
+            .arg(session_target(&session.name)),
+    );
 
     if let Ok(output) = output {
         output.status.success()
@@ -72,20 +89,80 @@ pub fn has_session(session: &Session) -> bool {
     }
 }
 
+/// Captures the contents of the active pane of a session, with escape sequences
+/// Equivalent to: `tmux capture-pane -ep -t <session_name>:`
+pub fn capture_pane(client: &impl TmuxClient, session: &Session) -> TmuxResult<String> {
+    let output = client.output(
+        TmuxCommand::new()
+            .arg("capture-pane")
+            .arg("-ep")
+            .arg("-t")
+            .arg(session_target(&session.name)),
+    )?;
+
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout)?)
+    } else {
+        Err(Error::CapturePane(
+            session.name.clone(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Lists the sessions running in the tmux server, empty if there's no server
+/// Equivalent to: `tmux list-sessions -F <format>`
+pub fn list_sessions(client: &impl TmuxClient) -> TmuxResult<Vec<TmuxSession>> {
+    let output = client.output(
+        TmuxCommand::new()
+            .arg("list-sessions")
+            .arg("-F")
+            .arg(LIST_SESSIONS_FORMAT),
+    )?;
+
+    // tmux fails when the server isn't running
+    if !output.status.success() {
+        return Ok(vec![]);
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(TmuxSession::parse)
+        .collect())
+}
+
 /// Create tmux session
-/// Equivalent to: `tmux new-session -d -s <session_name> -c <session_path>`
-pub fn create_session(session: &Session) -> TmuxResult<()> {
-    let output = Command::new("tmux")
+/// Equivalent to: `tmux new-session -d -s <session_name> -c <session_path> [-n <window_name>] [-e <name>=<value>]... [<command>]`
+pub fn create_session(client: &impl TmuxClient, session: &Session) -> TmuxResult<()> {
+    let env = session.expanded_env();
+
+    let mut command = TmuxCommand::new();
+    command
         .arg("new-session")
         .arg("-d")
         .arg("-s")
         .arg(&session.name)
         .arg("-c")
-        .arg(&session.path)
-        .output()?;
+        .arg(&session.path);
+
+    if let Some(window_name) = &session.window_name {
+        command.arg("-n").arg(window_name);
+    }
+
+    for (name, value) in &env {
+        command.arg("-e").arg(format!("{name}={value}"));
+    }
+
+    if let Some(command_value) = &session.command {
+        command.arg(command_value);
+    }
+
+    let output = client.output(&command)?;
 
     if output.status.success() {
-        run_on_create(session)?;
+        set_environment(client, session, &env)?;
+        run_on_create(client, session)?;
+        select(client, session)?;
         Ok(())
     } else {
         Err(Error::Create(
@@ -97,12 +174,13 @@ pub fn create_session(session: &Session) -> TmuxResult<()> {
 
 /// Switch to tmux session
 /// Equivalent to: `tmux switch-client -t <session_name>:`
-pub fn switch_to(session: &Session) -> TmuxResult<()> {
-    let output = Command::new("tmux")
-        .arg("switch-client")
-        .arg("-t")
-        .arg(session_target(&session.name))
-        .output()?;
+pub fn switch_to(client: &impl TmuxClient, session: &Session) -> TmuxResult<()> {
+    let output = client.output(
+        TmuxCommand::new()
+            .arg("switch-client")
+            .arg("-t")
+            .arg(session_target(&session.name)),
+    )?;
 
     if output.status.success() {
         Ok(())
@@ -114,25 +192,39 @@ pub fn switch_to(session: &Session) -> TmuxResult<()> {
     }
 }
 
+/// Attach to tmux session from outside tmux, replacing the current process
+/// Equivalent to: `exec tmux attach-session -t <session_name>:`
+pub fn attach_to(client: &impl TmuxClient, session: &Session) -> TmuxResult<()> {
+    // `exec` only returns if tmux couldn't be executed, or in dry-run mode
+    client.exec(
+        TmuxCommand::new()
+            .arg("attach-session")
+            .arg("-t")
+            .arg(session_target(&session.name)),
+    )?;
+
+    Ok(())
+}
+
 /// Tmux session menu picker
 /// Equivalent to: `tmux display-menu -T ' muxi ' <session_name> <key> "run {switch_to_session}"`
-pub fn sessions_menu(sessions: &Sessions) -> TmuxResult<()> {
-    let mut tmux = Command::new("tmux"); // Prevent 'temporary value dropped while borrowed'
-    let tmux_command = tmux
+pub fn sessions_menu(client: &impl TmuxClient, sessions: &[(&Key, &Session)]) -> TmuxResult<()> {
+    let mut tmux_command = TmuxCommand::new();
+    tmux_command
         .arg("display-menu")
         .arg("-T")
         .arg("#[align=left fg=green] muxi ");
 
     // Define tmux menu items: {session_name} {key} {command}
     // Ex: "#[blue]dotfiles" "d" "run -b 'muxi sessions switch d'"
-    for (key, session) in &sessions.0 {
+    for (key, session) in sessions {
         tmux_command
             .arg(format!("#[fg=blue]{}", session.name))
             .arg(key.as_ref())
             .arg(format!("run -b '{}'", switch_session_command(key.as_ref())));
     }
 
-    let output = tmux_command.output()?;
+    let output = client.output(&tmux_command)?;
 
     if output.status.success() {
         Ok(())
@@ -143,26 +235,281 @@ pub fn sessions_menu(sessions: &Sessions) -> TmuxResult<()> {
     }
 }
 
+/// Menu of the discovered projects, opened as sessions when picked
+/// Ex: "#[blue]muxi" "" "run -b 'muxi projects switch /code/muxi'"
+pub fn projects_menu(client: &impl TmuxClient, projects: &[PathBuf]) -> TmuxResult<()> {
+    let mut tmux_command = TmuxCommand::new();
+    tmux_command
+        .arg("display-menu")
+        .arg("-T")
+        .arg("#[align=left fg=green] muxi projects ");
+
+    for project in projects {
+        let name = project
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        tmux_command
+            .arg(format!("#[fg=blue]{name}"))
+            .arg("")
+            .arg(format!(
+                "run -b {}",
+                conf_quote(&switch_project_command(project))
+            ));
+    }
+
+    let output = client.output(&tmux_command)?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::DisplayMenu(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+pub fn switch_project_command(path: &Path) -> String {
+    format!(
+        "muxi projects switch {}",
+        shell_quote(&path.to_string_lossy())
+    )
+}
+
 pub fn switch_session_command(key: &str) -> String {
     format!("muxi sessions switch {key}")
 }
 
+pub fn run_binding_command(key: &str, table: &str) -> String {
+    format!(
+        "muxi run-binding {} --table {}",
+        shell_quote(key),
+        shell_quote(table)
+    )
+}
+
 fn session_target(name: &str) -> String {
     format!("{name}:")
 }
 
-fn run_on_create(session: &Session) -> TmuxResult<()> {
+/// `<session_name>:<window>`, where `window` can be `<window>.<pane>`
+fn window_target(name: &str, window: &str) -> String {
+    format!("{}{window}", session_target(name))
+}
+
+/// Sets the session environment, inherited by every window created later
+/// Equivalent to: `tmux set-environment -t <session_name>: <name> <value>`
+fn set_environment(
+    client: &impl TmuxClient,
+    session: &Session,
+    env: &BTreeMap<String, String>,
+) -> TmuxResult<()> {
+    if env.is_empty() {
+        return Ok(());
+    }
+
+    let mut command = TmuxCommand::new();
+
+    for (name, value) in env {
+        command
+            .arg("set-environment")
+            .arg("-t")
+            .arg(session_target(&session.name))
+            .arg(name)
+            .arg(value)
+            .arg(";");
+    }
+
+    let output = client.output(&command)?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::Environment(
+            session.name.clone(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+fn run_on_create(client: &impl TmuxClient, session: &Session) -> TmuxResult<()> {
     for action in &session.on_create {
         match action {
-            OnCreateAction::NewWindow(new_window) => create_window(session, new_window)?,
+            OnCreateAction::NewWindow(new_window) => create_window(client, session, new_window)?,
+            OnCreateAction::SendKeys(send_keys) => type_keys(client, session, send_keys)?,
+            OnCreateAction::Run(run) => run_command(client, session, run)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Selects the window, and pane if given, set in `select`
+/// Equivalent to: `tmux select-window -t <session_name>:<window> \; select-pane -t <session_name>:<window>.<pane>`
+fn select(client: &impl TmuxClient, session: &Session) -> TmuxResult<()> {
+    let Some(select) = &session.select else {
+        return Ok(());
+    };
+
+    let window = select
+        .rsplit_once('.')
+        .map_or(select.as_str(), |(window, _)| window);
+
+    let mut command = TmuxCommand::new();
+    command
+        .arg("select-window")
+        .arg("-t")
+        .arg(window_target(&session.name, window));
+
+    if window != select {
+        command
+            .arg(";")
+            .arg("select-pane")
+            .arg("-t")
+            .arg(window_target(&session.name, select));
+    }
+
+    let output = client.output(&command)?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::Select(
+            session.name.clone(),
+            select.clone(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Runs the command with `sh -c` in the session path and environment
+/// Equivalent to: `cd <session_path> && env <env> sh -c <command>`
+fn run_command(client: &impl TmuxClient, session: &Session, run: &RunCommand) -> TmuxResult<()> {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&run.command)
+        .current_dir(&session.path)
+        .envs(session.expanded_env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        // So a timeout kills whatever the command started too
+        .process_group(0);
+
+    if dry_run() {
+        print_command(&command);
+        return Ok(());
+    }
+
+    if run.background {
+        command.stderr(Stdio::null()).spawn()?;
+        return Ok(());
+    }
+
+    // A file instead of a pipe: `npm run dev &` keeps a pipe open after `sh` exits
+    let mut stderr = tempfile::tempfile()?;
+    command.stderr(stderr.try_clone()?);
+
+    let error = match wait_with_timeout(&mut command.spawn()?, run.timeout())? {
+        Some(status) if status.success() => return Ok(()),
+        Some(_) => {
+            let mut output = String::new();
+            stderr.rewind()?;
+            stderr.read_to_string(&mut output)?;
+
+            Error::Run(
+                session.name.clone(),
+                run.command.clone(),
+                output.trim().to_string(),
+            )
         }
+        None => Error::RunTimeout(
+            session.name.clone(),
+            run.command.clone(),
+            run.timeout.unwrap_or_default(),
+        ),
+    };
+
+    if run.display_errors {
+        // The command's error is the one worth reporting
+        let _ = display_message(client, &format!("muxi: {error}"));
     }
 
+    Err(error)
+}
+
+/// Like `Child::wait`, but kills the child's process group after `timeout`
+/// Returns `None` if the process timed out
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> std::io::Result<Option<ExitStatus>> {
+    let Some(timeout) = timeout else {
+        return child.wait().map(Some);
+    };
+
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        if Instant::now() >= deadline {
+            kill_process_group(Pid::from_child(child), Signal::KILL)?;
+            child.wait()?;
+
+            return Ok(None);
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Shows a message in the status line of the attached clients
+/// Equivalent to: `tmux display-message <message>`
+fn display_message(client: &impl TmuxClient, message: &str) -> TmuxResult<()> {
+    // `#` starts a tmux format
+    client.output(
+        TmuxCommand::new()
+            .arg("display-message")
+            .arg(message.replace('#', "##")),
+    )?;
+
     Ok(())
 }
 
-fn create_window(session: &Session, new_window: &NewWindow) -> TmuxResult<()> {
-    let mut command = Command::new("tmux");
+/// Types the keys without pressing Enter
+/// Equivalent to: `tmux send-keys -t <session_name>:<target> -l <keys>`
+fn type_keys(client: &impl TmuxClient, session: &Session, send_keys: &SendKeys) -> TmuxResult<()> {
+    let target = send_keys.target.as_deref().unwrap_or_default();
+
+    let output = client.output(
+        TmuxCommand::new()
+            .arg("send-keys")
+            .arg("-t")
+            .arg(window_target(&session.name, target))
+            .arg("-l")
+            .arg(&send_keys.keys),
+    )?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::SendKeys(
+            session.name.clone(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+fn create_window(
+    client: &impl TmuxClient,
+    session: &Session,
+    new_window: &NewWindow,
+) -> TmuxResult<()> {
+    let mut command = TmuxCommand::new();
     command
         .arg("new-window")
         .arg("-d")
@@ -177,11 +524,15 @@ fn create_window(session: &Session, new_window: &NewWindow) -> TmuxResult<()> {
         .arg("-c")
         .arg(session.on_create_path(new_window.path.as_deref()));
 
+    for (name, value) in new_window.expanded_env() {
+        command.arg("-e").arg(format!("{name}={value}"));
+    }
+
     if let Some(command_value) = &new_window.command {
         command.arg(command_value);
     }
 
-    let output = command.output()?;
+    let output = client.output(&command)?;
 
     if output.status.success() {
         Ok(())
@@ -195,10 +546,329 @@ fn create_window(session: &Session, new_window: &NewWindow) -> TmuxResult<()> {
 
 #[cfg(test)]
 mod tests {
+    use crate::muxi::NewWindow;
+    use crate::tmux::RecordingClient;
+
     use super::*;
 
+    fn session() -> Session {
+        Session {
+            name: "muxi".into(),
+            path: "/code/muxi".into(),
+            on_create: vec![
+                OnCreateAction::NewWindow(NewWindow {
+                    path: Some("src".into()),
+                    name: Some("editor".into()),
+                    command: Some("nvim".into()),
+                    env: BTreeMap::new(),
+                }),
+                OnCreateAction::NewWindow(NewWindow {
+                    path: None,
+                    name: None,
+                    command: None,
+                    env: BTreeMap::new(),
+                }),
+            ],
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn session_target_disambiguates_dotted_session_names() {
         assert_eq!(session_target("project.with.dots"), "project.with.dots:");
     }
+
+    #[test]
+    fn create_session_runs_on_create_in_order() {
+        let client = RecordingClient::new();
+
+        create_session(&client, &session()).unwrap();
+
+        assert_eq!(
+            client.calls(),
+            vec![
+                vec!["new-session", "-d", "-s", "muxi", "-c", "/code/muxi"],
+                vec![
+                    "new-window",
+                    "-d",
+                    "-t",
+                    "muxi:",
+                    "-n",
+                    "editor",
+                    "-c",
+                    "/code/muxi/src",
+                    "nvim",
+                ],
+                vec!["new-window", "-d", "-t", "muxi:", "-c", "/code/muxi"],
+            ]
+        );
+    }
+
+    #[test]
+    fn create_session_sets_the_environment() {
+        let client = RecordingClient::new();
+        let session = Session {
+            on_create: vec![OnCreateAction::NewWindow(NewWindow {
+                env: BTreeMap::from([("RAILS_ENV".into(), "test".into())]),
+                ..Default::default()
+            })],
+            env: BTreeMap::from([
+                ("AWS_PROFILE".into(), "work".into()),
+                ("NODE_ENV".into(), "development".into()),
+            ]),
+            ..session()
+        };
+
+        create_session(&client, &session).unwrap();
+
+        assert_eq!(
+            client.calls(),
+            vec![
+                vec![
+                    "new-session",
+                    "-d",
+                    "-s",
+                    "muxi",
+                    "-c",
+                    "/code/muxi",
+                    "-e",
+                    "AWS_PROFILE=work",
+                    "-e",
+                    "NODE_ENV=development",
+                ],
+                vec![
+                    "set-environment",
+                    "-t",
+                    "muxi:",
+                    "AWS_PROFILE",
+                    "work",
+                    ";",
+                    "set-environment",
+                    "-t",
+                    "muxi:",
+                    "NODE_ENV",
+                    "development",
+                    ";",
+                ],
+                vec![
+                    "new-window",
+                    "-d",
+                    "-t",
+                    "muxi:",
+                    "-c",
+                    "/code/muxi",
+                    "-e",
+                    "RAILS_ENV=test",
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn create_session_names_the_first_window_and_selects_a_pane() {
+        let client = RecordingClient::new();
+        let session = Session {
+            window_name: Some("editor".into()),
+            command: Some("nvim".into()),
+            on_create: vec![OnCreateAction::SendKeys(SendKeys {
+                keys: "cargo test".into(),
+                target: Some("editor".into()),
+            })],
+            select: Some("editor.1".into()),
+            ..session()
+        };
+
+        create_session(&client, &session).unwrap();
+
+        assert_eq!(
+            client.calls(),
+            vec![
+                vec![
+                    "new-session",
+                    "-d",
+                    "-s",
+                    "muxi",
+                    "-c",
+                    "/code/muxi",
+                    "-n",
+                    "editor",
+                    "nvim",
+                ],
+                vec!["send-keys", "-t", "muxi:editor", "-l", "cargo test"],
+                vec![
+                    "select-window",
+                    "-t",
+                    "muxi:editor",
+                    ";",
+                    "select-pane",
+                    "-t",
+                    "muxi:editor.1",
+                ],
+            ]
+        );
+    }
+
+    fn run_session(run: RunCommand) -> Session {
+        Session {
+            name: "muxi".into(),
+            path: std::env::temp_dir(),
+            on_create: vec![OnCreateAction::Run(run)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn run_command_runs_in_the_session_path_and_environment() {
+        let client = RecordingClient::new();
+        let mut session = run_session(RunCommand {
+            command: r#"test "$(pwd -P)" = "$(cd "$EXPECTED_PWD" && pwd -P)""#.into(),
+            ..Default::default()
+        });
+        session.env.insert(
+            "EXPECTED_PWD".into(),
+            std::env::temp_dir().display().to_string(),
+        );
+
+        create_session(&client, &session).unwrap();
+    }
+
+    #[test]
+    fn run_command_reports_stderr_and_displays_it() {
+        let client = RecordingClient::new();
+        let session = run_session(RunCommand {
+            command: "echo 'no compose file' >&2; exit 1".into(),
+            display_errors: true,
+            ..Default::default()
+        });
+
+        let result = create_session(&client, &session);
+
+        assert!(
+            matches!(&result, Err(Error::Run(name, _, stderr)) if name == "muxi" && stderr == "no compose file")
+        );
+        assert_eq!(
+            client.calls().last().unwrap(),
+            &vec![
+                "display-message".to_string(),
+                format!("muxi: {}", result.unwrap_err())
+            ]
+        );
+    }
+
+    #[test]
+    fn run_command_times_out() {
+        let client = RecordingClient::new();
+        let session = run_session(RunCommand {
+            command: "sleep 5".into(),
+            timeout: Some(1),
+            ..Default::default()
+        });
+
+        let result = create_session(&client, &session);
+
+        assert!(matches!(result, Err(Error::RunTimeout(_, command, 1)) if command == "sleep 5"));
+        // Not displayed unless asked
+        assert_eq!(client.calls().len(), 1);
+    }
+
+    #[test]
+    fn run_command_times_out_and_kills_what_it_started() {
+        let temp = tempfile::tempdir().unwrap();
+        let marker = temp.path().join("marker");
+        let client = RecordingClient::new();
+        let mut session = run_session(RunCommand {
+            command: r#"(sleep 1.5; touch "$MARKER") & wait"#.into(),
+            timeout: Some(1),
+            ..Default::default()
+        });
+        session
+            .env
+            .insert("MARKER".into(), marker.display().to_string());
+
+        let result = create_session(&client, &session);
+        std::thread::sleep(Duration::from_secs(1));
+
+        assert!(matches!(result, Err(Error::RunTimeout(..))));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn run_command_doesnt_wait_for_background_processes() {
+        let client = RecordingClient::new();
+        let session = run_session(RunCommand {
+            command: "sleep 5 &".into(),
+            timeout: Some(10),
+            ..Default::default()
+        });
+
+        let started = Instant::now();
+
+        assert!(create_session(&client, &session).is_ok());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn run_command_zero_timeout_waits() {
+        let client = RecordingClient::new();
+        let session = run_session(RunCommand {
+            command: "sleep 0.2".into(),
+            timeout: Some(0),
+            ..Default::default()
+        });
+
+        assert!(create_session(&client, &session).is_ok());
+    }
+
+    #[test]
+    fn create_session_skips_on_create_when_it_fails() {
+        let client = RecordingClient::new().failing("new-session");
+
+        let result = create_session(&client, &session());
+
+        assert!(
+            matches!(result, Err(Error::Create(name, stderr)) if name == "muxi" && stderr == "new-session failed")
+        );
+        assert_eq!(client.calls().len(), 1);
+    }
+
+    #[test]
+    fn has_session_checks_the_exit_status() {
+        let running = RecordingClient::new();
+        let stopped = RecordingClient::new().failing("has-session");
+
+        assert!(has_session(&running, &session()));
+        assert!(!has_session(&stopped, &session()));
+        assert_eq!(stopped.calls(), vec![vec!["has-session", "-t", "muxi:"]]);
+    }
+
+    #[test]
+    fn list_sessions_parses_the_output() {
+        let client =
+            RecordingClient::new().with_stdout("list-sessions", "1:2:1700000000:muxi:/code/muxi\n");
+
+        let sessions = list_sessions(&client).unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, "muxi");
+        assert!(sessions[0].is_attached());
+    }
+
+    #[test]
+    fn projects_menu_switches_to_the_picked_project() {
+        let client = RecordingClient::new();
+
+        projects_menu(&client, &["/code/it's mine".into()]).unwrap();
+
+        assert_eq!(
+            client.calls(),
+            vec![vec![
+                "display-menu",
+                "-T",
+                "#[align=left fg=green] muxi projects ",
+                "#[fg=blue]it's mine",
+                "",
+                r#"run -b "muxi projects switch '/code/it'\\''s mine'""#,
+            ]]
+        );
+    }
 }
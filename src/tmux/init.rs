@@ -1,8 +1,9 @@
-use std::process::Command;
+use crate::muxi::{BindingKind, Sessions, Settings};
 
-use crate::muxi::{Sessions, Settings};
-
-use super::{Error, Popup, TmuxResult, switch_session_command};
+use super::{
+    Error, Popup, TmuxClient, TmuxCommand, TmuxResult, dry_run, run_binding_command, socket,
+    switch_session_command,
+};
 
 /// Checks if it's run within a tmux session
 #[inline]
@@ -13,12 +14,31 @@ pub fn within_tmux() -> TmuxResult<()> {
 }
 
 /// Init system
-pub fn init(settings: &Settings, sessions: &Sessions) -> TmuxResult<()> {
-    within_tmux()?;
-    clear_muxi_table()?;
+pub fn init(client: &impl TmuxClient, settings: &Settings, sessions: &Sessions) -> TmuxResult<()> {
+    // An explicit socket can be initialized from outside tmux, and a dry run
+    // only prints the commands
+    if socket().is_none() && !dry_run() {
+        within_tmux()?;
+    }
+
+    clear_muxi_table(client)?;
+
+    let output = client.output(&init_command(settings, sessions))?;
+
+    if !output.status.success() {
+        return Err(Error::Init(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
 
-    let mut tmux_command = Command::new("tmux");
+    Ok(())
+}
 
+/// Options and bindings of `muxi init`, chained with `;`
+pub fn init_command(settings: &Settings, sessions: &Sessions) -> TmuxCommand {
+    let mut tmux_command = TmuxCommand::new();
+
+    set_options(&mut tmux_command, settings);
     bind_table_prefix(&mut tmux_command, settings);
 
     if settings.uppercase_overrides {
@@ -28,27 +48,21 @@ pub fn init(settings: &Settings, sessions: &Sessions) -> TmuxResult<()> {
     bind_settings(&mut tmux_command, settings);
     bind_sessions(&mut tmux_command, sessions);
 
-    let output = tmux_command.output()?;
-
-    if !output.status.success() {
-        return Err(Error::Init(
-            String::from_utf8_lossy(&output.stderr).trim().to_string(),
-        ));
-    }
+    tmux_command
+}
 
-    Ok(())
+/// `tmux unbind -aq -T muxi`
+pub fn clear_muxi_table_command() -> TmuxCommand {
+    let mut tmux_command = TmuxCommand::new();
+    tmux_command.arg("unbind").arg("-aq").arg("-T").arg("muxi");
+    tmux_command
 }
 
 /// Runs `tmux unbind -aq -T muxi`
 /// Cannot be ran alongside binding creation because it fails to bind anything
 #[inline]
-fn clear_muxi_table() -> TmuxResult<()> {
-    let output = Command::new("tmux")
-        .arg("unbind")
-        .arg("-aq")
-        .arg("-T")
-        .arg("muxi")
-        .output()?;
+fn clear_muxi_table(client: &impl TmuxClient) -> TmuxResult<()> {
+    let output = client.output(&clear_muxi_table_command())?;
 
     if !output.status.success() {
         return Err(Error::Init(
@@ -59,9 +73,18 @@ fn clear_muxi_table() -> TmuxResult<()> {
     Ok(())
 }
 
+/// Sets the options defined in the settings
+/// Equivalent to: `tmux set -g <name> <value>`
+#[inline]
+fn set_options(tmux_command: &mut TmuxCommand, settings: &Settings) {
+    for (scope, name, value) in settings.options.iter() {
+        tmux_command.args(scope.set_args(name, value)).arg(";");
+    }
+}
+
 /// tmux bind <settings.prefix> switch-client -T muxi
 #[inline]
-fn bind_table_prefix(tmux_command: &mut Command, settings: &Settings) {
+fn bind_table_prefix(tmux_command: &mut TmuxCommand, settings: &Settings) {
     tmux_command.arg("bind");
 
     // Bind at root table if no tmux prefix
@@ -79,45 +102,62 @@ fn bind_table_prefix(tmux_command: &mut Command, settings: &Settings) {
 
 /// Generates bindings defined in the settings
 #[inline]
-fn bind_settings(tmux_command: &mut Command, settings: &Settings) {
-    for (key, binding) in &settings.bindings {
-        tmux_command
-            .arg("bind")
-            .arg("-T")
-            .arg("muxi")
-            .arg(key.as_ref());
-
-        if let Some(Popup {
-            title,
-            width,
-            height,
-        }) = &binding.popup
-        {
-            tmux_command
-                .arg("popup")
-                .arg("-w")
-                .arg(width)
-                .arg("-h")
-                .arg(height)
-                .arg("-b")
-                .arg("rounded")
-                .arg("-E");
-
-            if let Some(title) = title {
-                tmux_command.arg("-T").arg(title);
+fn bind_settings(tmux_command: &mut TmuxCommand, settings: &Settings) {
+    let popup_default_dir = if settings.use_current_pane_path {
+        "#{pane_current_path}"
+    } else {
+        "#{session_path}"
+    };
+
+    let bindings = settings
+        .bindings
+        .iter()
+        .flat_map(|(key, bindings)| bindings.iter().map(move |binding| (key, binding)));
+
+    for (key, binding) in bindings {
+        tmux_command.arg("bind").arg("-T").arg(&binding.table);
+
+        if binding.repeatable {
+            tmux_command.arg("-r");
+        }
+
+        if let Some(note) = &binding.note {
+            tmux_command.arg("-N").arg(note);
+        }
+
+        tmux_command.arg(key.as_ref());
+
+        match binding.kind() {
+            BindingKind::Tmux => {
+                // tmux parses a single argument as a command string
+                tmux_command.arg(&binding.command);
+            }
+            BindingKind::Shell => {
+                tmux_command.arg("run").arg(&binding.command);
+            }
+            BindingKind::Popup => {
+                let default_popup = Popup::default();
+                let popup = binding.popup.as_ref().unwrap_or(&default_popup);
+
+                tmux_command
+                    .args(popup.args(popup_default_dir))
+                    .arg(&binding.command);
+            }
+            BindingKind::Lua => {
+                tmux_command
+                    .arg("run")
+                    .arg(run_binding_command(key.as_ref(), &binding.table));
             }
-        } else {
-            tmux_command.arg("run");
         }
 
-        tmux_command.arg(&binding.command).arg(";");
+        tmux_command.arg(";");
     }
 }
 
 /// Generates bindings for all the muxi sessions
 /// Equivalent to: `tmux bind -T muxi <session_key> run -b 'muxi sessions switch <session_key>'`
 #[inline]
-fn bind_sessions(tmux_command: &mut Command, sessions: &Sessions) {
+fn bind_sessions(tmux_command: &mut TmuxCommand, sessions: &Sessions) {
     for key in sessions.0.keys() {
         tmux_command
             .arg("bind")
@@ -134,7 +174,7 @@ fn bind_sessions(tmux_command: &mut Command, sessions: &Sessions) {
 /// Generates uppercase overrides
 /// Equivalent to: `tmux bind -T muxi <uppercase_letter> run-shell "muxi sessions set j && tmux display 'bound current session to j'"`
 #[inline]
-fn bind_uppercase_overrides(tmux_command: &mut Command) {
+fn bind_uppercase_overrides(tmux_command: &mut TmuxCommand) {
     for key in 'A'..='Z' {
         let command = format!(
             "muxi sessions set {} && tmux display 'bound current session to {}'",
@@ -152,3 +192,296 @@ fn bind_uppercase_overrides(tmux_command: &mut Command) {
             .arg(";");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::muxi::{Binding, Session};
+    use crate::tmux::{OptionValue, RecordingClient};
+
+    use super::*;
+
+    fn args(command: &TmuxCommand) -> Vec<String> {
+        command
+            .get_args()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn set_options_chains_every_scope() {
+        let mut settings = Settings::default();
+        settings
+            .options
+            .global
+            .insert("mouse".into(), OptionValue::Bool(true));
+        settings
+            .options
+            .server
+            .insert("escape-time".into(), OptionValue::Integer(0));
+        settings
+            .options
+            .window
+            .insert("mode-keys".into(), OptionValue::String("vi".into()));
+
+        let mut tmux_command = TmuxCommand::new();
+        set_options(&mut tmux_command, &settings);
+
+        assert_eq!(
+            args(&tmux_command),
+            vec![
+                "set",
+                "-s",
+                "escape-time",
+                "0",
+                ";",
+                "set",
+                "-g",
+                "mouse",
+                "on",
+                ";",
+                "set",
+                "-gw",
+                "mode-keys",
+                "vi",
+                ";",
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_settings_uses_table_repeat_and_note() {
+        let mut settings = Settings::default();
+        settings.bindings.insert(
+            "C-h".into(),
+            vec![Binding {
+                command: "tmux select-pane -L".into(),
+                table: "root".into(),
+                repeatable: true,
+                note: Some("Select left pane".into()),
+                ..Default::default()
+            }],
+        );
+
+        let mut tmux_command = TmuxCommand::new();
+        bind_settings(&mut tmux_command, &settings);
+
+        assert_eq!(
+            args(&tmux_command),
+            vec![
+                "bind",
+                "-T",
+                "root",
+                "-r",
+                "-N",
+                "Select left pane",
+                "C-h",
+                "run",
+                "tmux select-pane -L",
+                ";",
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_settings_binds_a_key_in_each_table() {
+        let mut settings = Settings::default();
+        settings.bindings.insert(
+            "h".into(),
+            vec![
+                Binding {
+                    command: "muxi sessions switch h".into(),
+                    ..Default::default()
+                },
+                Binding {
+                    command: "send -X cursor-left".into(),
+                    kind: Some(BindingKind::Tmux),
+                    table: "copy-mode-vi".into(),
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let mut tmux_command = TmuxCommand::new();
+        bind_settings(&mut tmux_command, &settings);
+
+        assert_eq!(
+            args(&tmux_command),
+            vec![
+                "bind",
+                "-T",
+                "muxi",
+                "h",
+                "run",
+                "muxi sessions switch h",
+                ";",
+                "bind",
+                "-T",
+                "copy-mode-vi",
+                "h",
+                "send -X cursor-left",
+                ";",
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_settings_generates_each_kind() {
+        let mut settings = Settings::default();
+        let bindings = [
+            ("a", Some(BindingKind::Tmux), "split-window -h"),
+            ("b", None, "muxi sessions switch x"),
+            ("c", Some(BindingKind::Popup), "htop"),
+            ("d", Some(BindingKind::Lua), ""),
+        ];
+
+        for (key, kind, command) in bindings {
+            settings.bindings.insert(
+                key.into(),
+                vec![Binding {
+                    command: command.into(),
+                    kind,
+                    ..Default::default()
+                }],
+            );
+        }
+
+        let mut tmux_command = TmuxCommand::new();
+        bind_settings(&mut tmux_command, &settings);
+
+        assert_eq!(
+            args(&tmux_command),
+            vec![
+                "bind",
+                "-T",
+                "muxi",
+                "a",
+                "split-window -h",
+                ";",
+                "bind",
+                "-T",
+                "muxi",
+                "b",
+                "run",
+                "muxi sessions switch x",
+                ";",
+                "bind",
+                "-T",
+                "muxi",
+                "c",
+                "popup",
+                "-w",
+                "75%",
+                "-h",
+                "75%",
+                "-b",
+                "rounded",
+                "-d",
+                "#{session_path}",
+                "-E",
+                "htop",
+                ";",
+                "bind",
+                "-T",
+                "muxi",
+                "d",
+                "run",
+                "muxi run-binding d --table muxi",
+                ";",
+            ]
+        );
+    }
+
+    #[test]
+    fn init_clears_the_table_then_chains_every_binding() {
+        let client = RecordingClient::new();
+
+        let mut settings = Settings {
+            muxi_prefix: "Space".into(),
+            uppercase_overrides: false,
+            ..Default::default()
+        };
+        settings
+            .options
+            .global
+            .insert("mouse".into(), OptionValue::Bool(true));
+        settings.bindings.insert(
+            "e".into(),
+            vec![Binding {
+                command: "nvim".into(),
+                ..Default::default()
+            }],
+        );
+
+        let sessions = Sessions(BTreeMap::from([(
+            "m".into(),
+            Session {
+                name: "muxi".into(),
+                path: "/code/muxi".into(),
+                ..Default::default()
+            },
+        )]));
+
+        let result = temp_env::with_vars(
+            [
+                ("TMUX", Some("/tmp/tmux-1000/default,1,0")),
+                ("MUXI_TMUX_SOCKET", None),
+            ],
+            || init(&client, &settings, &sessions),
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(
+            client.calls(),
+            vec![
+                vec!["unbind", "-aq", "-T", "muxi"],
+                vec![
+                    "set",
+                    "-g",
+                    "mouse",
+                    "on",
+                    ";",
+                    "bind",
+                    "Space",
+                    "switch-client",
+                    "-T",
+                    "muxi",
+                    ";",
+                    "bind",
+                    "-T",
+                    "muxi",
+                    "e",
+                    "run",
+                    "nvim",
+                    ";",
+                    "bind",
+                    "-T",
+                    "muxi",
+                    "m",
+                    "run",
+                    "-b",
+                    "muxi sessions switch m",
+                    ";",
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn init_stops_when_the_table_cant_be_cleared() {
+        let client = RecordingClient::new().failing("unbind");
+
+        let result = temp_env::with_vars(
+            [
+                ("TMUX", Some("/tmp/tmux-1000/default,1,0")),
+                ("MUXI_TMUX_SOCKET", None),
+            ],
+            || init(&client, &Settings::default(), &Sessions(BTreeMap::new())),
+        );
+
+        assert!(matches!(result, Err(Error::Init(stderr)) if stderr == "unbind failed"));
+        assert_eq!(client.calls().len(), 1);
+    }
+}
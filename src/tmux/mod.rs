@@ -1,11 +1,24 @@
+mod client;
+mod conf;
+mod dry_run;
 mod error;
 mod helpers;
 mod init;
 mod key;
+mod option;
 mod popup;
+mod session;
+mod shell;
+mod socket;
 
+pub use client::*;
+pub use conf::*;
+pub use dry_run::*;
 pub use error::*;
 pub use helpers::*;
 pub use init::*;
 pub use key::*;
+pub use option::*;
 pub use popup::*;
+pub use session::*;
+pub use socket::*;
@@ -0,0 +1,234 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Value of a tmux option, rendered the way tmux expects it
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(untagged)]
+pub enum OptionValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    List(Vec<OptionValue>),
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(true) => f.write_str("on"),
+            Self::Bool(false) => f.write_str("off"),
+            Self::Integer(value) => value.fmt(f),
+            Self::String(value) => value.fmt(f),
+            Self::List(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(" ")?;
+                    }
+
+                    value.fmt(f)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Which tmux options a value is set on
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionScope {
+    /// Global session option: `set -g`
+    #[default]
+    Global,
+    /// Server option: `set -s`
+    Server,
+    /// Global window option: `set -gw`
+    Window,
+    /// Option of the current session: `set`
+    Session,
+}
+
+impl OptionScope {
+    fn flags(self) -> &'static str {
+        match self {
+            Self::Global => "-g",
+            Self::Server => "-s",
+            Self::Window => "-gw",
+            Self::Session => "-",
+        }
+    }
+
+    /// Arguments for `tmux` to set `name` to `value` in this scope
+    pub fn set_args(self, name: &str, value: &OptionValue) -> Vec<String> {
+        let mut args = vec!["set".to_string()];
+
+        if self != Self::Session {
+            args.push(self.flags().to_string());
+        }
+
+        args.push(name.to_string());
+        args.push(value.to_string());
+        args
+    }
+
+    /// Arguments for `tmux` to unset `name` in this scope
+    pub fn unset_args(self, name: &str) -> Vec<String> {
+        vec![
+            "set".to_string(),
+            format!("{}u", self.flags()),
+            name.to_string(),
+        ]
+    }
+}
+
+impl fmt::Display for OptionScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Global => "global",
+            Self::Server => "server",
+            Self::Window => "window",
+            Self::Session => "session",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// A tmux option value and its scope
+/// Written either as a bare value or as `{ value = ..., scope = "server" }`
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(from = "TmuxOptionRepr")]
+pub struct TmuxOption {
+    pub value: OptionValue,
+    pub scope: OptionScope,
+}
+
+impl TmuxOption {
+    pub fn set_args(&self, name: &str) -> Vec<String> {
+        self.scope.set_args(name, &self.value)
+    }
+}
+
+impl From<OptionValue> for TmuxOption {
+    fn from(value: OptionValue) -> Self {
+        Self {
+            value,
+            scope: OptionScope::default(),
+        }
+    }
+}
+
+impl From<&str> for TmuxOption {
+    fn from(value: &str) -> Self {
+        OptionValue::String(value.to_string()).into()
+    }
+}
+
+impl From<String> for TmuxOption {
+    fn from(value: String) -> Self {
+        OptionValue::String(value).into()
+    }
+}
+
+impl Serialize for TmuxOption {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.scope == OptionScope::default() {
+            self.value.serialize(serializer)
+        } else {
+            TmuxOptionRepr::Scoped {
+                value: self.value.clone(),
+                scope: self.scope,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum TmuxOptionRepr {
+    Scoped {
+        value: OptionValue,
+        #[serde(default)]
+        scope: OptionScope,
+    },
+    Value(OptionValue),
+}
+
+impl From<TmuxOptionRepr> for TmuxOption {
+    fn from(repr: TmuxOptionRepr) -> Self {
+        match repr {
+            TmuxOptionRepr::Scoped { value, scope } => Self { value, scope },
+            TmuxOptionRepr::Value(value) => value.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mlua::{Lua, LuaSerdeExt, Value as LuaValue};
+
+    use super::*;
+
+    fn from_lua(code: &str) -> TmuxOption {
+        let lua = Lua::new();
+        let value = lua.load(code).eval::<LuaValue>().unwrap();
+
+        lua.from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_option_values_render_for_tmux() {
+        assert_eq!(from_lua("return true").value.to_string(), "on");
+        assert_eq!(from_lua("return false").value.to_string(), "off");
+        assert_eq!(from_lua("return 15").value.to_string(), "15");
+        assert_eq!(from_lua("return 'mocha'").value.to_string(), "mocha");
+        assert_eq!(
+            from_lua("return { 'ssh', 'psql', 5 }").value.to_string(),
+            "ssh psql 5"
+        );
+    }
+
+    #[test]
+    fn test_option_scope() {
+        let option = from_lua("return { value = 5, scope = 'server' }");
+
+        assert_eq!(
+            option,
+            TmuxOption {
+                value: OptionValue::Integer(5),
+                scope: OptionScope::Server,
+            }
+        );
+        assert_eq!(from_lua("return 'x'").scope, OptionScope::Global);
+    }
+
+    #[test]
+    fn test_set_and_unset_args() {
+        let value = OptionValue::Bool(true);
+
+        assert_eq!(
+            OptionScope::Global.set_args("@foo", &value),
+            vec!["set", "-g", "@foo", "on"]
+        );
+        assert_eq!(
+            OptionScope::Window.set_args("@foo", &value),
+            vec!["set", "-gw", "@foo", "on"]
+        );
+        assert_eq!(
+            OptionScope::Session.set_args("@foo", &value),
+            vec!["set", "@foo", "on"]
+        );
+        assert_eq!(
+            OptionScope::Server.unset_args("@foo"),
+            vec!["set", "-su", "@foo"]
+        );
+        assert_eq!(
+            OptionScope::Session.unset_args("@foo"),
+            vec!["set", "-u", "@foo"]
+        );
+    }
+}
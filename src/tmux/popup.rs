@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -7,8 +9,163 @@ pub struct Popup {
     pub width: String,
     #[serde(default = "default_popup_dimension")]
     pub height: String,
+    /// Horizontal position (`-x`), e.g. `"C"`, `"R"`, `"10"`
+    pub x: Option<String>,
+    /// Vertical position (`-y`), e.g. `"C"`, `"S"`, `"10"`
+    pub y: Option<String>,
+    /// Border lines (`-b`): single, rounded, double, heavy, simple, padded or none
+    #[serde(default = "default_popup_border")]
+    pub border: String,
+    /// Popup style (`-s`), e.g. `"bg=black"`
+    pub style: Option<String>,
+    /// Border style (`-S`), e.g. `"fg=blue"`
+    pub border_style: Option<String>,
+    /// Working directory (`-d`)
+    /// Defaults to the session path, or the pane path with `use_current_pane_path`
+    pub dir: Option<String>,
+    /// Environment variables (`-e`)
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub close_on_exit: CloseOnExit,
+}
+
+impl Popup {
+    /// Arguments for `tmux popup`, before the command
+    pub fn args(&self, default_dir: &str) -> Vec<String> {
+        let mut args = vec![
+            "popup".to_string(),
+            "-w".to_string(),
+            self.width.clone(),
+            "-h".to_string(),
+            self.height.clone(),
+            "-b".to_string(),
+            self.border.clone(),
+            "-d".to_string(),
+            self.dir.as_deref().unwrap_or(default_dir).to_string(),
+        ];
+
+        let flags = [
+            ("-x", &self.x),
+            ("-y", &self.y),
+            ("-s", &self.style),
+            ("-S", &self.border_style),
+            ("-T", &self.title),
+        ];
+
+        for (flag, value) in flags {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value.clone());
+            }
+        }
+
+        for (name, value) in &self.env {
+            args.push("-e".to_string());
+            args.push(format!("{name}={value}"));
+        }
+
+        if let Some(flag) = self.close_on_exit.flag() {
+            args.push(flag.to_string());
+        }
+
+        args
+    }
+}
+
+impl Default for Popup {
+    fn default() -> Self {
+        Self {
+            title: None,
+            width: default_popup_dimension(),
+            height: default_popup_dimension(),
+            x: None,
+            y: None,
+            border: default_popup_border(),
+            style: None,
+            border_style: None,
+            dir: None,
+            env: BTreeMap::new(),
+            close_on_exit: CloseOnExit::default(),
+        }
+    }
+}
+
+/// When the popup closes after its command exits
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseOnExit {
+    /// `-E`
+    #[default]
+    Always,
+    /// Only if the command succeeded: `-EE`
+    Success,
+    /// Keep it open until closed manually
+    Never,
+}
+
+impl CloseOnExit {
+    fn flag(self) -> Option<&'static str> {
+        match self {
+            Self::Always => Some("-E"),
+            Self::Success => Some("-EE"),
+            Self::Never => None,
+        }
+    }
 }
 
 fn default_popup_dimension() -> String {
     "75%".to_string()
 }
+
+fn default_popup_border() -> String {
+    "rounded".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_popup_args() {
+        assert_eq!(
+            Popup::default().args("#{session_path}"),
+            vec![
+                "popup",
+                "-w",
+                "75%",
+                "-h",
+                "75%",
+                "-b",
+                "rounded",
+                "-d",
+                "#{session_path}",
+                "-E"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_popup_args() {
+        let popup = Popup {
+            title: Some(" htop ".into()),
+            x: Some("R".into()),
+            y: Some("0".into()),
+            border: "double".into(),
+            style: Some("bg=black".into()),
+            border_style: Some("fg=blue".into()),
+            dir: Some("~/code".into()),
+            env: BTreeMap::from([("FOO".into(), "bar".into())]),
+            close_on_exit: CloseOnExit::Success,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            popup.args("#{session_path}"),
+            vec![
+                "popup", "-w", "75%", "-h", "75%", "-b", "double", "-d", "~/code", "-x", "R", "-y",
+                "0", "-s", "bg=black", "-S", "fg=blue", "-T", " htop ", "-e", "FOO=bar", "-EE"
+            ]
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Format passed to `tmux list-sessions -F`, one session per line
+/// Fields are separated by `:`, which tmux doesn't allow in session names (tabs
+/// get escaped by tmux outside UTF-8 locales), and the path goes last
+pub(super) const LIST_SESSIONS_FORMAT: &str =
+    "#{session_attached}:#{session_windows}:#{session_activity}:#{session_name}:#{session_path}";
+
+/// A session running in the tmux server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmuxSession {
+    pub name: String,
+    pub path: PathBuf,
+    /// Number of attached clients
+    pub attached: usize,
+    pub windows: usize,
+    pub last_activity: SystemTime,
+}
+
+impl TmuxSession {
+    pub fn is_attached(&self) -> bool {
+        self.attached > 0
+    }
+
+    /// Parses a line of `tmux list-sessions -F LIST_SESSIONS_FORMAT`
+    pub(super) fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(5, ':');
+
+        let attached = fields.next()?.parse().ok()?;
+        let windows = fields.next()?.parse().ok()?;
+        let activity = fields.next()?.parse().ok()?;
+        let name = fields.next()?.to_string();
+        let path = fields.next()?.into();
+
+        Some(Self {
+            name,
+            path,
+            attached,
+            windows,
+            last_activity: UNIX_EPOCH + Duration::from_secs(activity),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_sessions_line() {
+        let session = TmuxSession::parse("2:3:1700000000:my project:/code/my:project").unwrap();
+
+        assert_eq!(
+            session,
+            TmuxSession {
+                name: "my project".into(),
+                path: "/code/my:project".into(),
+                attached: 2,
+                windows: 3,
+                last_activity: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            }
+        );
+        assert!(session.is_attached());
+    }
+
+    #[test]
+    fn test_parse_invalid_line() {
+        assert_eq!(TmuxSession::parse("nope:1:0:muxi:/code/muxi"), None);
+        assert_eq!(TmuxSession::parse("muxi"), None);
+    }
+}
@@ -0,0 +1,27 @@
+/// Quotes an argument for POSIX shells
+pub(super) fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("switch-client"), "switch-client");
+        assert_eq!(shell_quote("/code/muxi"), "/code/muxi");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("#{session_path}"), "'#{session_path}'");
+        assert_eq!(
+            shell_quote("muxi run-binding 'x'"),
+            r"'muxi run-binding '\''x'\'''"
+        );
+    }
+}
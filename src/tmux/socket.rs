@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Socket selected for this process with `--socket-name`/`--socket-path`
+static SOCKET: OnceLock<Socket> = OnceLock::new();
+
+/// tmux server to talk to instead of the default one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Socket {
+    /// `tmux -L <name>`
+    Name(String),
+    /// `tmux -S <path>`
+    Path(PathBuf),
+}
+
+impl Socket {
+    /// Reads `MUXI_TMUX_SOCKET`: a path if it contains a `/`, a socket name otherwise
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var("MUXI_TMUX_SOCKET").ok()?;
+
+        Self::parse(&value)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        if value.is_empty() {
+            None
+        } else if value.contains('/') {
+            Some(Self::Path(value.into()))
+        } else {
+            Some(Self::Name(value.to_string()))
+        }
+    }
+}
+
+/// Makes every tmux command of this process target `socket`
+pub fn set_socket(socket: Socket) {
+    // Only set once, from the CLI arguments
+    let _ = SOCKET.set(socket);
+}
+
+/// Socket set from the CLI, falling back to `MUXI_TMUX_SOCKET`
+pub fn socket() -> Option<Socket> {
+    SOCKET.get().cloned().or_else(Socket::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_socket() {
+        assert_eq!(Socket::parse("work"), Some(Socket::Name("work".into())));
+        assert_eq!(
+            Socket::parse("/tmp/tmux-1000/work"),
+            Some(Socket::Path("/tmp/tmux-1000/work".into()))
+        );
+        assert_eq!(Socket::parse(""), None);
+    }
+
+    #[test]
+    fn test_socket_from_env() {
+        temp_env::with_var("MUXI_TMUX_SOCKET", Some("scratch"), || {
+            assert_eq!(Socket::from_env(), Some(Socket::Name("scratch".into())));
+        });
+
+        temp_env::with_var("MUXI_TMUX_SOCKET", None::<&str>, || {
+            assert_eq!(Socket::from_env(), None);
+        });
+    }
+}
@@ -0,0 +1,460 @@
+//! End-to-end tests against a private tmux server
+//!
+//! Every test starts its own server on a temp socket (`tmux -L <uuid>`)
+//! and points muxi at temp config and data directories.
+//! Skipped when tmux isn't installed.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+struct TmuxServer {
+    socket: String,
+    root: PathBuf,
+    control_client: Option<Child>,
+}
+
+impl TmuxServer {
+    /// Starts a server with a detached `base` session, `None` if tmux isn't installed
+    fn start() -> Option<Self> {
+        if Command::new("tmux").arg("-V").output().is_err() {
+            eprintln!("tmux not found, skipping");
+            return None;
+        }
+
+        let socket = format!("muxi-test-{}", Uuid::new_v4());
+        let root = std::env::temp_dir().join(&socket);
+
+        std::fs::create_dir_all(root.join("config")).unwrap();
+        std::fs::create_dir_all(root.join("data")).unwrap();
+
+        let server = Self {
+            socket,
+            root,
+            control_client: None,
+        };
+
+        // Ignore the user's tmux.conf
+        let output = Command::new("tmux")
+            .arg("-L")
+            .arg(&server.socket)
+            .arg("-f")
+            .arg("/dev/null")
+            .arg("new-session")
+            .arg("-d")
+            .arg("-s")
+            .arg("base")
+            .arg("-c")
+            .arg(&server.root)
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        Some(server)
+    }
+
+    fn config_path(&self) -> PathBuf {
+        self.root.join("config")
+    }
+
+    fn data_path(&self) -> PathBuf {
+        self.root.join("data")
+    }
+
+    fn write_sessions(&self, sessions: &str) {
+        std::fs::write(self.config_path().join("sessions.toml"), sessions).unwrap();
+    }
+
+    fn read_sessions(&self) -> String {
+        std::fs::read_to_string(self.config_path().join("sessions.toml")).unwrap()
+    }
+
+    /// Runs a tmux command on the private server and returns its stdout
+    fn tmux(&self, args: &[&str]) -> String {
+        let output = Command::new("tmux")
+            .arg("-L")
+            .arg(&self.socket)
+            .args(args)
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Runs muxi against the private server, as if it were run from inside it
+    fn muxi(&self, args: &[&str]) -> Output {
+        let tmux_env = self.tmux(&["display-message", "-p", "#{socket_path},#{pid},0"]);
+
+        Command::new(env!("CARGO_BIN_EXE_muxi"))
+            .arg("--socket-name")
+            .arg(&self.socket)
+            .args(args)
+            .env("MUXI_CONFIG_PATH", self.config_path())
+            .env("MUXI_DATA_PATH", self.data_path())
+            .env("TMUX", tmux_env.trim())
+            .env_remove("MUXI_TMUX_SOCKET")
+            .output()
+            .unwrap()
+    }
+
+    /// Attaches a control mode client to `base`, so there's a client to switch
+    fn attach_client(&mut self) {
+        let child = Command::new("tmux")
+            .arg("-L")
+            .arg(&self.socket)
+            .arg("-C")
+            .arg("attach-session")
+            .arg("-t")
+            .arg("base:")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        self.control_client = Some(child);
+
+        wait_until(|| !self.tmux(&["list-clients"]).trim().is_empty());
+    }
+
+    fn list_keys(&self) -> String {
+        self.tmux(&["list-keys", "-T", "muxi"])
+    }
+
+    fn client_session(&self) -> String {
+        self.tmux(&["list-clients", "-F", "#{client_session}"])
+            .trim()
+            .to_string()
+    }
+}
+
+impl Drop for TmuxServer {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.control_client.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+
+        let _ = Command::new("tmux")
+            .arg("-L")
+            .arg(&self.socket)
+            .arg("kill-server")
+            .output();
+
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+fn assert_success(output: &Output) {
+    assert!(output.status.success(), "{}", stderr(output));
+}
+
+fn wait_until(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn sessions_toml(entries: &[(&str, &str, &Path)]) -> String {
+    entries
+        .iter()
+        .map(|(key, name, path)| {
+            format!(
+                "[{key}]\nname = \"{name}\"\npath = \"{}\"\n",
+                path.display()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn init_binds_the_prefix_and_every_session() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+
+    server.write_sessions(&sessions_toml(&[
+        ("d", "dotfiles", &server.root),
+        ("m", "muxi", &server.root),
+    ]));
+
+    assert_success(&server.muxi(&["init"]));
+
+    let prefix_keys = server.tmux(&["list-keys", "-T", "prefix"]);
+    assert!(
+        prefix_keys.contains("switch-client -T muxi"),
+        "{prefix_keys}"
+    );
+
+    let keys = server.list_keys();
+    assert!(keys.contains("muxi sessions switch d"), "{keys}");
+    assert!(keys.contains("muxi sessions switch m"), "{keys}");
+}
+
+#[test]
+fn dry_run_prints_the_commands_without_running_them() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+
+    server.write_sessions(&sessions_toml(&[("d", "dotfiles", &server.root)]));
+
+    let output = server.muxi(&["--dry-run", "init"]);
+    assert_success(&output);
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(&format!("tmux -L {} unbind -aq -T muxi", server.socket)),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("bind -T muxi d run -b 'muxi sessions switch d'"),
+        "{stdout}"
+    );
+
+    // Nothing was bound, not even the muxi table
+    let keys = server.tmux(&["list-keys"]);
+    assert!(!keys.contains("muxi sessions switch d"), "{keys}");
+}
+
+#[test]
+fn exported_tmux_conf_binds_like_init() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+
+    server.write_sessions(&sessions_toml(&[("d", "dotfiles", &server.root)]));
+
+    let conf = server.root.join("muxi.conf");
+    let conf_path = conf.to_string_lossy();
+    assert_success(&server.muxi(&["export", "tmux-conf", "--output", &conf_path]));
+
+    server.tmux(&["source-file", &conf_path]);
+
+    let keys = server.list_keys();
+    assert!(keys.contains("muxi sessions switch d"), "{keys}");
+    assert!(keys.contains("muxi sessions set a"), "{keys}");
+}
+
+#[test]
+fn exported_tmux_conf_skips_uninstalled_plugins() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+
+    std::fs::write(
+        server.config_path().join("init.lua"),
+        r#"muxi.config.plugins = { "tmux-plugins/tmux-sensible" }"#,
+    )
+    .unwrap();
+
+    let output = server.muxi(&["export", "tmux-conf"]);
+    assert_success(&output);
+
+    let conf = String::from_utf8_lossy(&output.stdout);
+    assert!(conf.contains("# tmux-sensible\n# Not installed"), "{conf}");
+    assert!(!conf.contains("run-shell"), "{conf}");
+    assert!(stderr(&output).contains("tmux-sensible isn't installed"));
+}
+
+#[test]
+fn sessions_set_and_delete_update_the_bindings() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+
+    assert_success(&server.muxi(&["init"]));
+    assert!(!server.list_keys().contains("muxi sessions switch x"));
+
+    assert_success(&server.muxi(&["sessions", "set", "x"]));
+
+    let sessions = server.read_sessions();
+    assert!(sessions.contains("x = { name = \"base\""), "{sessions}");
+    assert!(server.list_keys().contains("muxi sessions switch x"));
+
+    assert_success(&server.muxi(&["sessions", "delete", "x"]));
+
+    assert!(!server.read_sessions().contains("x = "));
+    assert!(!server.list_keys().contains("muxi sessions switch x"));
+}
+
+#[test]
+fn sessions_switch_creates_the_session_and_runs_on_create() {
+    let Some(mut server) = TmuxServer::start() else {
+        return;
+    };
+
+    std::fs::create_dir_all(server.root.join("src")).unwrap();
+
+    server.write_sessions(&format!(
+        r#"[m]
+name = "muxi"
+path = "{}"
+on_create = [
+  {{ new_window = {{ name = "editor", path = "src", command = "sleep 60" }} }},
+  {{ new_window = {{ name = "shell" }} }},
+]
+"#,
+        server.root.display()
+    ));
+
+    server.attach_client();
+    assert_eq!(server.client_session(), "base");
+
+    assert_success(&server.muxi(&["sessions", "switch", "m"]));
+
+    assert_eq!(server.client_session(), "muxi");
+
+    let windows = server.tmux(&[
+        "list-windows",
+        "-t",
+        "muxi:",
+        "-F",
+        "#{window_name}:#{pane_current_path}",
+    ]);
+    let windows = windows.lines().skip(1).collect::<Vec<_>>();
+
+    assert_eq!(
+        windows,
+        vec![
+            format!("editor:{}", server.root.join("src").display()),
+            format!("shell:{}", server.root.display()),
+        ]
+    );
+
+    // Switching to a running session doesn't run on_create again
+    assert_success(&server.muxi(&["sessions", "switch", "m"]));
+
+    let window_count = server
+        .tmux(&["list-windows", "-t", "muxi:"])
+        .lines()
+        .count();
+
+    assert_eq!(window_count, 3);
+}
+
+#[test]
+fn sessions_switch_names_the_first_window_and_selects_another() {
+    let Some(mut server) = TmuxServer::start() else {
+        return;
+    };
+
+    server.write_sessions(&format!(
+        r#"[m]
+name = "muxi"
+path = "{}"
+window_name = "editor"
+command = "sleep 60"
+select = "shell"
+on_create = [
+  {{ new_window = {{ name = "shell" }} }},
+  {{ send_keys = {{ target = "shell", keys = "echo ready" }} }},
+]
+"#,
+        server.root.display()
+    ));
+
+    server.attach_client();
+    assert_success(&server.muxi(&["sessions", "switch", "m"]));
+
+    let windows = server.tmux(&["list-windows", "-t", "muxi:", "-F", "#{window_name}"]);
+    assert_eq!(windows.lines().collect::<Vec<_>>(), vec!["editor", "shell"]);
+
+    let active_window = server.tmux(&["display-message", "-p", "-t", "muxi:", "#{window_name}"]);
+    assert_eq!(active_window.trim(), "shell");
+}
+
+#[test]
+fn projects_switch_bookmarks_a_discovered_project() {
+    let Some(mut server) = TmuxServer::start() else {
+        return;
+    };
+
+    let code = server.root.join("code");
+    std::fs::create_dir_all(code.join("muxi/.git")).unwrap();
+    std::fs::create_dir_all(code.join("notes")).unwrap();
+
+    std::fs::write(
+        server.config_path().join("init.lua"),
+        format!(
+            "muxi.config.projects.roots = {{ {:?} }}",
+            code.display().to_string()
+        ),
+    )
+    .unwrap();
+
+    let output = server.muxi(&["projects", "list"]);
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        code.join("muxi").display().to_string()
+    );
+
+    server.attach_client();
+
+    let project = code.join("muxi");
+    assert_success(&server.muxi(&[
+        "projects",
+        "switch",
+        "--bookmark",
+        project.to_str().unwrap(),
+    ]));
+
+    assert_eq!(server.client_session(), "muxi");
+    assert!(server.read_sessions().contains(r#"a = { name = "muxi""#));
+    assert!(server.list_keys().contains("muxi sessions switch a"));
+
+    // Switching again reuses the bookmark
+    assert_success(&server.muxi(&[
+        "projects",
+        "switch",
+        "--bookmark",
+        project.to_str().unwrap(),
+    ]));
+    assert!(!server.read_sessions().contains("b = "));
+}
+
+#[test]
+fn projects_switch_dry_run_prints_the_bookmark() {
+    let Some(server) = TmuxServer::start() else {
+        return;
+    };
+
+    let project = server.root.join("muxi");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let output = server.muxi(&[
+        "--dry-run",
+        "projects",
+        "switch",
+        "--bookmark",
+        project.to_str().unwrap(),
+    ]);
+    assert_success(&output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("# Bookmark muxi to a in "), "{stdout}");
+    assert!(
+        stdout.contains(&format!(
+            r#"# a = {{ name = "muxi", path = "{}" }}"#,
+            project.display()
+        )),
+        "{stdout}"
+    );
+    assert!(stdout.contains("new-session"), "{stdout}");
+
+    assert!(!server.read_sessions().contains("a = "));
+}